@@ -0,0 +1,421 @@
+use crate::entities::{ArchiveEntity, EntityKind};
+use crate::util::result::{Error, Result};
+use crate::{
+    AccountRepository, ArchiveRepository, BotRepository, ChannelRepository, Database,
+    MemberRepository, MessageRepository, SettingsRepository, UnreadRepository, UserRepository,
+};
+use chrono::Utc;
+use mongodb::bson::{doc, from_document, to_document, Bson, Document};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::convert::TryFrom;
+use std::io::{BufRead, Write};
+
+/// Bumped whenever the layout of an archive changes. Readers accept any
+/// version up to their own.
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// Entities fetched or restored per query.
+const PAGE_SIZE: i64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArchiveFormat {
+    /// One JSON object per line.
+    Json,
+    /// Consecutive MessagePack values.
+    MessagePack,
+}
+
+/// A single entry in an archive. The first entry is always the header.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum Record {
+    Header {
+        version: u32,
+        created_at: String,
+    },
+    /// A document as canonical extended JSON, so types such as dates and
+    /// 64-bit integers survive the round trip.
+    Document {
+        section: String,
+        document: Value,
+    },
+}
+
+/// Streams documents into an archive.
+pub struct ArchiveWriter<W: Write> {
+    writer: W,
+    format: ArchiveFormat,
+}
+
+impl<W: Write> ArchiveWriter<W> {
+    pub fn new(writer: W, format: ArchiveFormat) -> Result<Self> {
+        let mut archive = ArchiveWriter { writer, format };
+        archive.write_record(&Record::Header {
+            version: ARCHIVE_VERSION,
+            created_at: Utc::now().to_rfc3339(),
+        })?;
+
+        Ok(archive)
+    }
+
+    pub fn write(&mut self, section: &str, document: Document) -> Result<()> {
+        self.write_record(&Record::Document {
+            section: section.to_string(),
+            document: Bson::Document(document).into_canonical_extjson(),
+        })
+    }
+
+    /// Write an entity as it would be stored.
+    pub fn write_entity<T: Serialize>(&mut self, section: &str, entity: &T) -> Result<()> {
+        self.write(section, to_document(entity).map_err(archive_error)?)
+    }
+
+    /// Write a stored entity, in the section named after its kind.
+    pub fn write_stored(&mut self, entity: &ArchiveEntity) -> Result<()> {
+        let mut document = to_document(entity).map_err(archive_error)?;
+        let section = document.get_str("kind").map_err(archive_error)?.to_string();
+        let entity = document.get_document_mut("entity").map_err(archive_error)?;
+        self.write(&section, std::mem::take(entity))
+    }
+
+    /// Flush the archive, handing back the underlying writer.
+    pub fn finish(mut self) -> Result<W> {
+        self.writer.flush().map_err(archive_error)?;
+        Ok(self.writer)
+    }
+
+    fn write_record(&mut self, record: &Record) -> Result<()> {
+        match self.format {
+            ArchiveFormat::Json => {
+                serde_json::to_writer(&mut self.writer, record).map_err(archive_error)?;
+                self.writer.write_all(b"\n").map_err(archive_error)
+            }
+            ArchiveFormat::MessagePack => {
+                rmp_serde::encode::write_named(&mut self.writer, record).map_err(archive_error)
+            }
+        }
+    }
+}
+
+/// Reads `(section, document)` pairs back out of an archive.
+pub struct ArchiveReader<R: BufRead> {
+    reader: R,
+    format: ArchiveFormat,
+    version: u32,
+}
+
+impl<R: BufRead> ArchiveReader<R> {
+    /// Open an archive, checking its header.
+    pub fn new(reader: R, format: ArchiveFormat) -> Result<Self> {
+        let mut archive = ArchiveReader {
+            reader,
+            format,
+            version: 0,
+        };
+
+        archive.version = match archive.read_record()? {
+            Some(Record::Header { version, .. }) if version <= ARCHIVE_VERSION => version,
+            Some(Record::Header { version, .. }) => {
+                return Err(Error::ArchiveError {
+                    reason: format!(
+                        "archive version {} is newer than {}",
+                        version, ARCHIVE_VERSION
+                    ),
+                })
+            }
+            _ => {
+                return Err(Error::ArchiveError {
+                    reason: "missing header".to_string(),
+                })
+            }
+        };
+
+        Ok(archive)
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    fn read_record(&mut self) -> Result<Option<Record>> {
+        match self.format {
+            ArchiveFormat::Json => {
+                let mut line = String::new();
+                loop {
+                    line.clear();
+                    if self.reader.read_line(&mut line).map_err(archive_error)? == 0 {
+                        return Ok(None);
+                    }
+
+                    if !line.trim().is_empty() {
+                        return serde_json::from_str(&line).map(Some).map_err(archive_error);
+                    }
+                }
+            }
+            ArchiveFormat::MessagePack => {
+                if self.reader.fill_buf().map_err(archive_error)?.is_empty() {
+                    return Ok(None);
+                }
+
+                rmp_serde::from_read(&mut self.reader)
+                    .map(Some)
+                    .map_err(archive_error)
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for ArchiveReader<R> {
+    type Item = Result<(String, Document)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = match self.read_record() {
+            Ok(Some(record)) => record,
+            Ok(None) => return None,
+            Err(error) => return Some(Err(error)),
+        };
+
+        Some(match record {
+            Record::Document { section, document } => match Bson::try_from(document) {
+                Ok(Bson::Document(document)) => Ok((section, document)),
+                Ok(_) => Err(Error::ArchiveError {
+                    reason: format!("entry in {} is not a document", section),
+                }),
+                Err(error) => Err(archive_error(error)),
+            },
+            Record::Header { .. } => Err(Error::ArchiveError {
+                reason: "unexpected header".to_string(),
+            }),
+        })
+    }
+}
+
+/// Write every stored entity to `archive`, returning how many were written.
+pub async fn export<W: Write>(db: &Database, archive: &mut ArchiveWriter<W>) -> Result<u64> {
+    let mut total = 0;
+    for kind in EntityKind::ALL {
+        let mut after = None;
+        loop {
+            let page = db.get_entities_page(*kind, after, PAGE_SIZE).await?;
+            for entity in &page.entities {
+                archive.write_stored(entity)?;
+                total += 1;
+            }
+
+            after = match page.last {
+                Some(last) => Some(last),
+                None => break,
+            };
+        }
+    }
+
+    Ok(total)
+}
+
+/// Write everything stored about a user to `archive`, for data subject
+/// requests, returning how many entries were written. Entries are labelled
+/// by what they hold rather than by collection, so a takeout cannot be
+/// passed to `import`.
+pub async fn export_user<W: Write>(
+    db: &Database,
+    user_id: &str,
+    archive: &mut ArchiveWriter<W>,
+) -> Result<u64> {
+    let mut total = 1;
+    archive.write_entity("profile", &db.get_user_by_id(user_id).await?)?;
+
+    for subscription in db.get_accounts_subscriptions(vec![user_id]).await? {
+        archive.write(
+            "push_subscriptions",
+            doc! {
+                "endpoint": subscription.endpoint,
+                "p256dh": subscription.keys.p256dh,
+                "auth": subscription.keys.auth
+            },
+        )?;
+        total += 1;
+    }
+
+    if let Some(settings) = db.get_user_settings_doc(user_id, vec![]).await? {
+        archive.write("settings", settings)?;
+        total += 1;
+    }
+
+    for member in db.get_users_memberships(user_id).await? {
+        archive.write_entity("memberships", &member)?;
+        total += 1;
+    }
+
+    for bot in db.get_bots_owned_by_user_id(user_id).await? {
+        archive.write_entity("bots", &bot)?;
+        total += 1;
+    }
+
+    for channel in db
+        .get_sms_dms_groups_where_user_is_recipient(vec![], user_id)
+        .await?
+    {
+        archive.write_entity("channels", &channel)?;
+        total += 1;
+    }
+
+    for unread in db.get_unreads_for_user(user_id).await? {
+        archive.write("unreads", unread)?;
+        total += 1;
+    }
+
+    // Messages carry their attachments' metadata with them.
+    let mut after: Option<String> = None;
+    loop {
+        let page = db
+            .get_messages_by_author(user_id, after.as_deref(), PAGE_SIZE)
+            .await?;
+
+        for message in &page.messages {
+            archive.write_entity("messages", message)?;
+            total += 1;
+        }
+
+        after = match page.last {
+            Some(last) => Some(last),
+            None => break,
+        };
+    }
+
+    Ok(total)
+}
+
+/// Restore every entity in `archive`, replacing existing entities with the
+/// same ID. Returns how many entities were restored.
+pub async fn import<R: BufRead>(db: &Database, archive: ArchiveReader<R>) -> Result<u64> {
+    let mut total = 0;
+    let mut batch = vec![];
+
+    for entry in archive {
+        let (section, document) = entry?;
+        batch.push(read_stored(&section, document)?);
+        total += 1;
+
+        if batch.len() as i64 >= PAGE_SIZE {
+            db.restore_entities(std::mem::take(&mut batch)).await?;
+        }
+    }
+
+    if !batch.is_empty() {
+        db.restore_entities(batch).await?;
+    }
+
+    Ok(total)
+}
+
+/// Turn an entry written by `ArchiveWriter::write_stored` back into an
+/// entity.
+fn read_stored(section: &str, document: Document) -> Result<ArchiveEntity> {
+    from_document(doc! { "kind": section, "entity": document }).map_err(|error| {
+        Error::ArchiveError {
+            reason: format!("entry in {}: {}", section, error),
+        }
+    })
+}
+
+fn archive_error<E: ToString>(error: E) -> Error {
+    Error::ArchiveError {
+        reason: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_stored, ArchiveFormat, ArchiveReader, ArchiveWriter};
+    use crate::entities::{ArchiveEntity, EntityKind};
+    use chrono::{TimeZone, Utc};
+    use mongodb::bson::{doc, to_bson, to_document, Bson, Document};
+
+    fn round_trip(format: ArchiveFormat) {
+        let document = doc! {
+            "_id": "01F7ZSBSFHQ8TA81725KQCSDDP",
+            "created_at": Utc.timestamp_millis(1_620_000_000_123),
+            "size": 5_000_000_000i64,
+            "count": 7,
+            "nested": { "tags": ["a", "b"], "flag": true }
+        };
+
+        let mut writer = ArchiveWriter::new(vec![], format).unwrap();
+        writer.write("things", document.clone()).unwrap();
+        writer
+            .write_stored(&ArchiveEntity::Account(document.clone()))
+            .unwrap();
+        let bytes = writer.finish().unwrap();
+
+        let mut reader = ArchiveReader::new(&bytes[..], format).unwrap();
+        assert_eq!(reader.version(), super::ARCHIVE_VERSION);
+
+        let entries: Vec<(String, Document)> = reader.by_ref().map(Result::unwrap).collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0], ("things".to_string(), document.clone()));
+        assert_eq!(entries[1].0, "account");
+
+        let (section, stored) = entries[1].clone();
+        match read_stored(&section, stored).unwrap() {
+            ArchiveEntity::Account(account) => assert_eq!(account, document),
+            entity => panic!("read back {:?}", entity),
+        }
+    }
+
+    fn stored_round_trip(format: ArchiveFormat) {
+        let entities = ArchiveEntity::samples();
+        let mut writer = ArchiveWriter::new(vec![], format).unwrap();
+        for entity in &entities {
+            writer.write_stored(entity).unwrap();
+        }
+        let bytes = writer.finish().unwrap();
+
+        let read: Vec<ArchiveEntity> = ArchiveReader::new(&bytes[..], format)
+            .unwrap()
+            .map(|entry| {
+                let (section, document) = entry.unwrap();
+                read_stored(&section, document).unwrap()
+            })
+            .collect();
+
+        assert_eq!(read.len(), entities.len());
+        for (written, read) in entities.iter().zip(&read) {
+            assert_eq!(to_document(written).unwrap(), to_document(read).unwrap());
+        }
+    }
+
+    #[test]
+    fn samples_cover_every_kind() {
+        let kinds: Vec<Bson> = ArchiveEntity::samples()
+            .iter()
+            .map(|entity| to_document(entity).unwrap().get("kind").cloned().unwrap())
+            .collect();
+        let expected: Vec<Bson> = EntityKind::ALL
+            .iter()
+            .map(|kind| to_bson(kind).unwrap())
+            .collect();
+
+        assert_eq!(kinds, expected);
+    }
+
+    #[test]
+    fn json_round_trip() {
+        round_trip(ArchiveFormat::Json);
+    }
+
+    #[test]
+    fn message_pack_round_trip() {
+        round_trip(ArchiveFormat::MessagePack);
+    }
+
+    #[test]
+    fn json_stored_round_trip() {
+        stored_round_trip(ArchiveFormat::Json);
+    }
+
+    #[test]
+    fn message_pack_stored_round_trip() {
+        stored_round_trip(ArchiveFormat::MessagePack);
+    }
+}
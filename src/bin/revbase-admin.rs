@@ -0,0 +1,311 @@
+use chrono::Duration;
+use revbase::archive::{self, ArchiveFormat, ArchiveReader, ArchiveWriter};
+use revbase::drivers::mongo::MongoDB;
+use revbase::jobs::{self, BotDisposal};
+use revbase::util::config::Config;
+use revbase::util::result::Error;
+use revbase::{
+    BanRepository, BotRepository, ChannelRepository, Database, Driver, InviteRepository,
+    MemberRepository, MessageRepository, ServerRepository, UserRepository,
+};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::PathBuf;
+use std::process::exit;
+
+const USAGE: &str = "Usage: revbase-admin [--config <file>] <command>
+
+Commands:
+    migrate [--dry-run]                 Apply pending migrations, or list what they would touch
+    status                              Show applied and pending migrations and index drift
+    user <id or username>               Print a user
+    rename <user id> <username>         Change a user's username
+    ban <server id> <user id> [reason]  Ban a user from a server, removing their membership
+    unban <server id> <user id>         Lift a server ban
+    delete-server <server id>           Delete a server and everything in it
+    purge-messages <days>               Remove messages soft-deleted more than <days> days ago
+    delete-account <user id> [--transfer-bots <user id>] [--scrub-messages]
+                                        Delete an account, anonymising the user
+    resume-jobs                         Finish jobs interrupted by an earlier run
+    show <kind> <id>                    Print a user, channel, server, message, bot or invite
+    export <file> [--msgpack]           Write every stored entity to an archive
+    import <file> [--msgpack]           Restore an archive, replacing matching entities
+    takeout <user id> <file> [--msgpack]
+                                        Write everything stored about a user to an archive
+
+Settings are read from the config file, if given, and REVOLT_* environment variables.";
+
+/// Failures are reported to the operator as text, whichever layer they come from.
+type Result<T> = std::result::Result<T, String>;
+
+#[async_std::main]
+async fn main() {
+    env_logger::init();
+
+    if let Err(message) = run(std::env::args().skip(1).collect()).await {
+        eprintln!("{}", message);
+        exit(1);
+    }
+}
+
+async fn run(mut args: Vec<String>) -> Result<()> {
+    let mut config_path = None;
+    if args.first().map(String::as_str) == Some("--config") {
+        if args.len() < 2 {
+            return Err(USAGE.to_string());
+        }
+
+        config_path = Some(PathBuf::from(args.remove(1)));
+        args.remove(0);
+    }
+
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    if matches!(args.as_slice(), [] | ["help"] | ["--help"] | ["-h"]) {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let db = connect(config_path).await?;
+
+    match args.as_slice() {
+        ["migrate"] => {
+            mongo(&db)?.migrate().await.map_err(describe)?;
+            println!("Database is up to date.");
+        }
+        ["migrate", "--dry-run"] => {
+            print(&mongo(&db)?.dry_run_migrations().await.map_err(describe)?)?
+        }
+        ["status"] => status(mongo(&db)?).await?,
+        ["user", target] => {
+            let user = match db.get_user_by_id(target).await {
+                Err(Error::UnknownUser) => db.get_user_by_username(target).await,
+                result => result,
+            };
+
+            print(&user.map_err(describe)?)?;
+        }
+        ["rename", id, username] => {
+            db.get_user_by_id(id).await.map_err(describe)?;
+            db.update_username(id, username).await.map_err(describe)?;
+            println!("Renamed {} to {}.", id, username);
+        }
+        ["ban", server, user, reason @ ..] => {
+            let reason = reason.join(" ");
+            let reason = if reason.is_empty() {
+                None
+            } else {
+                Some(reason.as_str())
+            };
+
+            db.get_server_by_id(server).await.map_err(describe)?;
+            db.get_user_by_id(user).await.map_err(describe)?;
+            // Ban first, so a failure never leaves the user kicked but free
+            // to rejoin.
+            db.add_server_ban(server, user, reason)
+                .await
+                .map_err(describe)?;
+            db.delete_server_member(server, user)
+                .await
+                .map_err(describe)?;
+            println!("Banned {} from {}.", user, server);
+        }
+        ["unban", server, user] => {
+            db.get_ban(server, user).await.map_err(describe)?;
+            db.delete_server_ban(server, user).await.map_err(describe)?;
+            println!("Unbanned {} from {}.", user, server);
+        }
+        ["delete-server", id] => {
+            db.delete_server(id).await.map_err(describe)?;
+            println!("Deleted server {}.", id);
+        }
+        ["purge-messages", days] => {
+            let days: i64 = days
+                .parse()
+                .ok()
+                .filter(|days| *days >= 0)
+                .ok_or_else(|| USAGE.to_string())?;
+            let purged = db
+                .purge_soft_deleted_messages(Duration::days(days))
+                .await
+                .map_err(describe)?;
+            println!("Purged {} messages.", purged.len());
+        }
+        ["delete-account", id, flags @ ..] => {
+            let mut bots = BotDisposal::Delete;
+            let mut scrub_messages = false;
+            let mut flags = flags;
+            while !flags.is_empty() {
+                flags = match flags {
+                    ["--transfer-bots", owner, rest @ ..] => {
+                        bots = BotDisposal::Transfer {
+                            owner: owner.to_string(),
+                        };
+                        rest
+                    }
+                    ["--scrub-messages", rest @ ..] => {
+                        scrub_messages = true;
+                        rest
+                    }
+                    _ => return Err(USAGE.to_string()),
+                };
+            }
+
+            jobs::delete_account(&db, id, bots, scrub_messages)
+                .await
+                .map_err(describe)?;
+            println!("Deleted account {}.", id);
+        }
+        ["resume-jobs"] => {
+            let resumed = jobs::resume_jobs(&db).await.map_err(describe)?;
+            println!(
+                "Finished {} jobs, skipped {} running elsewhere.",
+                resumed.finished, resumed.skipped
+            );
+
+            if !resumed.failed.is_empty() {
+                for (id, error) in &resumed.failed {
+                    eprintln!("Job {} failed: {}", id, error);
+                }
+
+                return Err(format!("{} jobs failed.", resumed.failed.len()));
+            }
+        }
+        ["show", kind, id] => match *kind {
+            "user" => print(&db.get_user_by_id(id).await.map_err(describe)?)?,
+            "channel" => print(&db.get_channel_by_id(id).await.map_err(describe)?)?,
+            "server" => print(&db.get_server_by_id(id).await.map_err(describe)?)?,
+            "message" => print(&db.get_message_by_id(id).await.map_err(describe)?)?,
+            "bot" => print(&db.get_bot_by_id(id).await.map_err(describe)?)?,
+            "invite" => print(&db.get_invite_by_id(id).await.map_err(describe)?)?,
+            _ => return Err(format!("Unknown kind {}.\n\n{}", kind, USAGE)),
+        },
+        ["export", path, flags @ ..] => {
+            let file = File::create(path).map_err(|error| error.to_string())?;
+            let mut writer =
+                ArchiveWriter::new(BufWriter::new(file), format(flags)?).map_err(describe)?;
+            let count = archive::export(&db, &mut writer).await.map_err(describe)?;
+            writer.finish().map_err(describe)?;
+            println!("Exported {} documents to {}.", count, path);
+        }
+        ["takeout", user, path, flags @ ..] => {
+            let file = File::create(path).map_err(|error| error.to_string())?;
+            let mut writer =
+                ArchiveWriter::new(BufWriter::new(file), format(flags)?).map_err(describe)?;
+            let count = archive::export_user(&db, user, &mut writer)
+                .await
+                .map_err(describe)?;
+            writer.finish().map_err(describe)?;
+            println!("Wrote {} entries about {} to {}.", count, user, path);
+        }
+        ["import", path, flags @ ..] => {
+            let file = File::open(path).map_err(|error| error.to_string())?;
+            let reader =
+                ArchiveReader::new(BufReader::new(file), format(flags)?).map_err(describe)?;
+            let count = archive::import(&db, reader).await.map_err(describe)?;
+            println!("Imported {} documents from {}.", count, path);
+        }
+        _ => return Err(USAGE.to_string()),
+    }
+
+    Ok(())
+}
+
+/// Connect without migrating, so that `status` and `migrate --dry-run` see
+/// the database as it is.
+async fn connect(config_path: Option<PathBuf>) -> Result<Database> {
+    let mut config = match config_path {
+        Some(path) => Config::from_file(&path).map_err(|problem| problem.to_string())?,
+        None => Config::default(),
+    };
+
+    let problems = config.apply_env();
+    if !problems.is_empty() {
+        let problems: Vec<String> = problems.iter().map(ToString::to_string).collect();
+        return Err(problems.join("\n"));
+    }
+
+    if config.mongo_uri.is_empty() {
+        return Err("mongo_uri: is required".to_string());
+    }
+
+    let mut options = config.mongo_options();
+    options.auto_migrate = false;
+
+    Database::new_from_mongo(&config.mongo_uri, options)
+        .await
+        .map_err(describe)
+}
+
+async fn status(mongo: &MongoDB) -> Result<()> {
+    println!("Applied migrations:");
+    for migration in mongo.applied_migrations().await.map_err(describe)? {
+        println!(
+            "    {} / {}: {} (applied {})",
+            migration.id, migration.date, migration.description, *migration.applied_at
+        );
+    }
+
+    let pending = mongo.pending_migrations().await.map_err(describe)?;
+    println!("Pending migrations:");
+    if pending.is_empty() {
+        println!("    none");
+    }
+
+    for migration in pending {
+        println!(
+            "    {} / {}: {}",
+            migration.id, migration.date, migration.description
+        );
+    }
+
+    let report = mongo.index_report().await.map_err(describe)?;
+    println!("Missing indexes: {}", list(&report.missing));
+    println!("Unexpected indexes: {}", list(&report.unexpected));
+    println!("Drifted indexes:");
+    if report.drifted.is_empty() {
+        println!("    none");
+    }
+
+    for drift in report.drifted {
+        println!(
+            "    {}.{}: expected {}, found {}",
+            drift.collection, drift.name, drift.expected, drift.actual
+        );
+    }
+
+    Ok(())
+}
+
+fn mongo(db: &Database) -> Result<&MongoDB> {
+    match &**db {
+        Driver::Mongo(mongo) => Ok(mongo),
+        _ => Err("This command needs a MongoDB database.".to_string()),
+    }
+}
+
+fn format(flags: &[&str]) -> Result<ArchiveFormat> {
+    match flags {
+        [] => Ok(ArchiveFormat::Json),
+        ["--msgpack"] => Ok(ArchiveFormat::MessagePack),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn print<T: Serialize>(value: &T) -> Result<()> {
+    let json = serde_json::to_string_pretty(value).map_err(|error| error.to_string())?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn list(items: &[String]) -> String {
+    if items.is_empty() {
+        "none".to_string()
+    } else {
+        items.join(", ")
+    }
+}
+
+fn describe(error: Error) -> String {
+    format!("{} ({})", error, error.kind())
+}
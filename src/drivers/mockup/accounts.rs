@@ -0,0 +1,33 @@
+use super::Mockup;
+use crate::entities::Subscription;
+use crate::repositories::AccountRepository;
+use crate::util::result::Result;
+use rocket::async_trait;
+use web_push::SubscriptionInfo;
+
+#[async_trait]
+impl AccountRepository for Mockup {
+    async fn get_accounts_subscriptions(
+        &self,
+        target_ids: Vec<&str>,
+    ) -> Result<Vec<SubscriptionInfo>> {
+        todo!()
+    }
+
+    async fn subscribe(
+        &self,
+        account_id: &str,
+        session_id: &str,
+        subscription: Subscription,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn unsubscribe(&self, account_id: &str, session_id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn delete_subscriptions(&self, account_id: &str) -> Result<()> {
+        todo!()
+    }
+}
@@ -0,0 +1,22 @@
+use super::Mockup;
+use crate::entities::{ArchiveEntity, ArchivePage, EntityKind};
+use crate::repositories::ArchiveRepository;
+use crate::util::result::Result;
+use mongodb::bson::Bson;
+use rocket::async_trait;
+
+#[async_trait]
+impl ArchiveRepository for Mockup {
+    async fn get_entities_page(
+        &self,
+        kind: EntityKind,
+        after: Option<Bson>,
+        limit: i64,
+    ) -> Result<ArchivePage> {
+        todo!()
+    }
+
+    async fn restore_entities(&self, entities: Vec<ArchiveEntity>) -> Result<()> {
+        todo!()
+    }
+}
@@ -0,0 +1,33 @@
+use super::Mockup;
+use crate::entities::File;
+use crate::repositories::AttachmentRepository;
+use crate::util::result::Result;
+use rocket::async_trait;
+
+#[async_trait]
+impl AttachmentRepository for Mockup {
+    async fn get_attachment(&self, id: &str, tag: &str, parent_type: &str) -> Result<File> {
+        todo!()
+    }
+
+    async fn link_attachment_to_parent(
+        &self,
+        id: &str,
+        parent_type: &str,
+        parent_id: &str,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn delete_attachment(&self, id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn delete_attachments(&self, ids: Vec<&str>) -> Result<()> {
+        todo!()
+    }
+
+    async fn delete_attachments_of_messages(&self, message_ids: Vec<&str>) -> Result<()> {
+        todo!()
+    }
+}
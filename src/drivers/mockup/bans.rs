@@ -0,0 +1,33 @@
+use super::Mockup;
+use crate::entities::Ban;
+use crate::repositories::BanRepository;
+use crate::util::result::Result;
+use rocket::async_trait;
+
+#[async_trait]
+impl BanRepository for Mockup {
+    async fn delete_server_ban(&self, server_id: &str, user_id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn is_user_banned(&self, server_id: &str, user_id: &str) -> Result<bool> {
+        todo!()
+    }
+
+    async fn get_ban(&self, server_id: &str, user_id: &str) -> Result<Ban> {
+        todo!()
+    }
+
+    async fn get_bans(&self, server_id: &str) -> Result<Vec<Ban>> {
+        todo!()
+    }
+
+    async fn add_server_ban(
+        &self,
+        server_id: &str,
+        user_id: &str,
+        reason: Option<&str>,
+    ) -> Result<()> {
+        todo!()
+    }
+}
@@ -0,0 +1,33 @@
+use super::Mockup;
+use crate::entities::Bot;
+use crate::repositories::BotRepository;
+use crate::util::result::Result;
+use mongodb::bson::Document;
+use rocket::async_trait;
+
+#[async_trait]
+impl BotRepository for Mockup {
+    async fn get_bot_by_id(&self, id: &str) -> Result<Bot> {
+        todo!()
+    }
+
+    async fn get_bot_count_owned_by_user(&self, user_id: &str) -> Result<u64> {
+        todo!()
+    }
+
+    async fn get_bots_owned_by_user_id(&self, id: &str) -> Result<Vec<Bot>> {
+        todo!()
+    }
+
+    async fn add_bot(&self, bot: &Bot) -> Result<()> {
+        todo!()
+    }
+
+    async fn delete_bot(&self, id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn apply_bot_changes(&self, id: &str, change_doc: Document) -> Result<()> {
+        todo!()
+    }
+}
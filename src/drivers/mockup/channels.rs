@@ -0,0 +1,118 @@
+use super::Mockup;
+use crate::entities::Channel;
+use crate::repositories::ChannelRepository;
+use crate::util::result::Result;
+use mongodb::bson::Document;
+use rocket::async_trait;
+
+#[async_trait]
+impl ChannelRepository for Mockup {
+    async fn get_channel_by_id(&self, id: &str) -> Result<Channel> {
+        todo!()
+    }
+
+    async fn does_channel_exist_by_nonce(&self, nonce: &str) -> Result<bool> {
+        todo!()
+    }
+
+    async fn remove_recipient_from_channel(
+        &self,
+        channel_id: &str,
+        recipient_id: &str,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn update_channel_role_permissions(
+        &self,
+        channel_id: &str,
+        role: &str,
+        permissions: i32,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn update_channel_permissions(&self, channel_id: &str, permissions: i32) -> Result<()> {
+        todo!()
+    }
+
+    async fn update_channel_default_permissions(
+        &self,
+        channel_id: &str,
+        default_permissions: i32,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn delete_server_channels_role_permissions(
+        &self,
+        server_id: &str,
+        role_id: &str,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn get_dm_channels_from_user(&self, user_id: &str) -> Result<Vec<Document>> {
+        todo!()
+    }
+
+    async fn get_dm_channel(&self, user_a: &str, user_b: &str) -> Result<Option<Document>> {
+        todo!()
+    }
+
+    async fn delete_all_channels_from_server(&self, server_id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn add_channel(&self, channel: &Channel) -> Result<()> {
+        todo!()
+    }
+
+    async fn delete_channel(&self, id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn add_recipient_to_channel(&self, channel_id: &str, recipient_id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn are_users_connected_in_dms_or_group(
+        &self,
+        user_a: &str,
+        user_b: &str,
+    ) -> Result<bool> {
+        todo!()
+    }
+
+    async fn get_sms_dms_groups_where_user_is_recipient(
+        &self,
+        channel_ids: Vec<&str>,
+        user_id: &str,
+    ) -> Result<Vec<Channel>> {
+        todo!()
+    }
+
+    async fn get_channel_ids_from_sms_dms_groups_where_user_is_recipient(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<String>> {
+        todo!()
+    }
+
+    async fn make_channel_inactive(&self, channel_id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn update_channel_owner(
+        &self,
+        channel_id: &str,
+        new_owner: &str,
+        old_owner: &str,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn apply_channel_changes(&self, channel_id: &str, change_doc: Document) -> Result<()> {
+        todo!()
+    }
+}
@@ -0,0 +1,28 @@
+use super::Mockup;
+use crate::entities::Invite;
+use crate::repositories::InviteRepository;
+use crate::util::result::Result;
+use rocket::async_trait;
+
+#[async_trait]
+impl InviteRepository for Mockup {
+    async fn delete_invites_associated_to_channel(&self, id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn get_invite_by_id(&self, id: &str) -> Result<Invite> {
+        todo!()
+    }
+
+    async fn add_invite(&self, invite: &Invite) -> Result<()> {
+        todo!()
+    }
+
+    async fn delete_invite(&self, id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn get_invites_of_server(&self, server_id: &str) -> Result<Vec<Invite>> {
+        todo!()
+    }
+}
@@ -0,0 +1,37 @@
+use super::Mockup;
+use crate::entities::Job;
+use crate::repositories::JobRepository;
+use crate::util::result::Result;
+use chrono::Duration;
+use rocket::async_trait;
+
+#[async_trait]
+impl JobRepository for Mockup {
+    async fn get_job(&self, id: &str) -> Result<Job> {
+        todo!()
+    }
+
+    async fn get_jobs(&self) -> Result<Vec<Job>> {
+        todo!()
+    }
+
+    async fn add_job(&self, job: &Job) -> Result<()> {
+        todo!()
+    }
+
+    async fn claim_job(&self, id: &str, owner: &str, lease: Duration) -> Result<bool> {
+        todo!()
+    }
+
+    async fn release_job(&self, id: &str, owner: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn set_job_step(&self, id: &str, owner: &str, step: i32) -> Result<bool> {
+        todo!()
+    }
+
+    async fn delete_job(&self, id: &str) -> Result<()> {
+        todo!()
+    }
+}
@@ -0,0 +1,62 @@
+use super::Mockup;
+use crate::entities::Member;
+use crate::repositories::MemberRepository;
+use crate::util::result::Result;
+use mongodb::bson::Document;
+use rocket::async_trait;
+
+#[async_trait]
+impl MemberRepository for Mockup {
+    async fn get_server_member(&self, server_id: &str, user_id: &str) -> Result<Member> {
+        todo!()
+    }
+
+    async fn get_server_members(&self, server_id: &str) -> Result<Vec<Member>> {
+        todo!()
+    }
+
+    async fn add_server_member(&self, server_id: &str, user_id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn delete_server_member(&self, server_id: &str, user_id: &str) -> Result<i64> {
+        todo!()
+    }
+
+    async fn get_server_member_count(&self, server_id: &str) -> Result<i64> {
+        todo!()
+    }
+
+    async fn get_users_memberships(&self, user_id: &str) -> Result<Vec<Member>> {
+        todo!()
+    }
+
+    async fn is_user_member_in_one_of_servers(
+        &self,
+        user_id: &str,
+        server_ids: Vec<&str>,
+    ) -> Result<bool> {
+        todo!()
+    }
+
+    async fn apply_server_member_changes(
+        &self,
+        server_id: &str,
+        user_id: &str,
+        change_doc: Document,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn delete_role_from_server_members(&self, server_id: &str, role_id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn get_server_memberships_by_ids(
+        &self,
+        user_id: &str,
+        server_ids: Vec<&str>,
+    ) -> Result<Vec<Member>> {
+        todo!()
+    }
+}
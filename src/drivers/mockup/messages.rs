@@ -0,0 +1,103 @@
+use super::Mockup;
+use crate::entities::microservice::january::Embed;
+use crate::entities::{Message, MessageFilter, MessagePage, Sort};
+use crate::repositories::MessageRepository;
+use crate::util::result::Result;
+use chrono::Duration;
+use mongodb::bson::Document;
+use rocket::async_trait;
+
+#[async_trait]
+impl MessageRepository for Mockup {
+    async fn get_message_by_id(&self, id: &str) -> Result<Message> {
+        todo!()
+    }
+
+    async fn set_message_updates(&self, message_id: &str, set_doc: Document) -> Result<()> {
+        todo!()
+    }
+
+    async fn get_ids_from_messages_with_attachments(
+        &self,
+        channel_id: &str,
+    ) -> Result<Vec<String>> {
+        todo!()
+    }
+
+    async fn delete_messages_from_channel(&self, channel_id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn add_message(&self, message: &Message) -> Result<()> {
+        todo!()
+    }
+
+    async fn add_embeds_to_message(&self, message_id: &str, embeds: &Vec<Embed>) -> Result<()> {
+        todo!()
+    }
+
+    async fn delete_message(&self, message_id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn get_messages_by_ids_and_channel(
+        &self,
+        message_ids: Vec<&str>,
+        channel_id: &str,
+    ) -> Result<Vec<Message>> {
+        todo!()
+    }
+
+    async fn search_messages(
+        &self,
+        channel_id: &str,
+        search: &str,
+        options_before: Option<&str>,
+        options_after: Option<&str>,
+        limit: i64,
+        sort: Sort,
+    ) -> Result<Vec<Message>> {
+        todo!()
+    }
+
+    async fn does_message_exist_by_nonce(&self, nonce: &str) -> Result<bool> {
+        todo!()
+    }
+
+    async fn delete_messages_bulk(
+        &self,
+        channel_id: &str,
+        filter: &MessageFilter,
+    ) -> Result<Vec<String>> {
+        todo!()
+    }
+
+    async fn soft_delete_message(&self, message_id: &str, deleted_by: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn restore_message(&self, message_id: &str, grace_period: Duration) -> Result<()> {
+        todo!()
+    }
+
+    async fn get_soft_deleted_messages(&self, channel_id: &str) -> Result<Vec<Message>> {
+        todo!()
+    }
+
+    async fn purge_soft_deleted_messages(&self, grace_period: Duration) -> Result<Vec<String>> {
+        todo!()
+    }
+
+    async fn get_messages_by_author(
+        &self,
+        author: &str,
+        after: Option<&str>,
+        limit: i64,
+    ) -> Result<MessagePage> {
+        todo!()
+    }
+
+    async fn scrub_messages(&self, message_ids: Vec<&str>) -> Result<()> {
+        todo!()
+    }
+}
@@ -0,0 +1,71 @@
+use super::Mockup;
+use crate::entities::Server;
+use crate::repositories::ServerRepository;
+use crate::util::result::Result;
+use mongodb::bson::Document;
+use rocket::async_trait;
+
+#[async_trait]
+impl ServerRepository for Mockup {
+    async fn get_server_by_id(&self, id: &str) -> Result<Server> {
+        todo!()
+    }
+
+    async fn update_server_permissions(
+        &self,
+        server_id: &str,
+        role_id: &str,
+        server_permissions: i32,
+        channel_permissions: i32,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn update_server_default_permissions(
+        &self,
+        server_id: &str,
+        server_permissions: i32,
+        channel_permissions: i32,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn apply_server_changes(&self, server_id: &str, change_doc: Document) -> Result<()> {
+        todo!()
+    }
+
+    async fn add_server(&self, server: &Server) -> Result<()> {
+        todo!()
+    }
+
+    async fn get_servers(&self, server_ids: Vec<&str>) -> Result<Vec<Server>> {
+        todo!()
+    }
+
+    async fn add_channel_to_server(&self, server_id: &str, channel_id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn create_role(
+        &self,
+        server_id: &str,
+        role_id: &str,
+        role_name: &str,
+        default_permission: i32,
+        default_permission_server: i32,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn delete_role(&self, server_id: &str, role_id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn does_server_exist_by_nonce(&self, nonce: &str) -> Result<bool> {
+        todo!()
+    }
+
+    async fn delete_server(&self, id: &str) -> Result<()> {
+        todo!()
+    }
+}
@@ -0,0 +1,29 @@
+use super::Mockup;
+use crate::entities::{UserSettingsEntry, UserSettingsSyncResult};
+use crate::repositories::SettingsRepository;
+use crate::util::result::Result;
+use mongodb::bson::Document;
+use rocket::async_trait;
+
+#[async_trait]
+impl SettingsRepository for Mockup {
+    async fn update_user_settings(&self, user_id: &str, set_doc: Document) -> Result<()> {
+        todo!()
+    }
+
+    async fn get_user_settings_doc(
+        &self,
+        user_id: &str,
+        option_keys: Vec<&str>,
+    ) -> Result<Option<Document>> {
+        todo!()
+    }
+
+    async fn sync_user_settings(
+        &self,
+        user_id: &str,
+        entries: Vec<UserSettingsEntry>,
+    ) -> Result<UserSettingsSyncResult> {
+        todo!()
+    }
+}
@@ -0,0 +1,68 @@
+use super::Mockup;
+use crate::entities::ChannelUnreadCount;
+use crate::repositories::UnreadRepository;
+use crate::util::result::Result;
+use mongodb::bson::Document;
+use rocket::async_trait;
+
+#[async_trait]
+impl UnreadRepository for Mockup {
+    async fn delete_channel_unreads(&self, channel_id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn delete_unreads_for_user(&self, user_id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn delete_multi_channel_unreads_for_user(
+        &self,
+        channel_ids: Vec<&str>,
+        user_id: &str,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn add_mentions_to_channel_unreads(
+        &self,
+        channel_id: &str,
+        mentions: Vec<&str>,
+        message: &str,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn add_channels_to_unreads_for_user(
+        &self,
+        channel_ids: Vec<&str>,
+        user_id: &str,
+        current_time: &str,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn get_unreads_for_user(&self, user_id: &str) -> Result<Vec<Document>> {
+        todo!()
+    }
+
+    async fn update_last_message_in_channel_unreads(
+        &self,
+        channel_id: &str,
+        user_id: &str,
+        message_id: &str,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn get_unread_counts_for_user(
+        &self,
+        user_id: &str,
+        channel_ids: Vec<&str>,
+    ) -> Result<Vec<ChannelUnreadCount>> {
+        todo!()
+    }
+
+    async fn mark_server_as_read(&self, server_id: &str, user_id: &str) -> Result<()> {
+        todo!()
+    }
+}
@@ -0,0 +1,161 @@
+use super::Mockup;
+use crate::entities::{BannedUser, RelationshipStatus, User};
+use crate::repositories::UserRepository;
+use crate::util::result::Result;
+use mongodb::bson::Document;
+use rocket::async_trait;
+
+#[async_trait]
+impl UserRepository for Mockup {
+    async fn get_user_by_id(&self, id: &str) -> Result<User> {
+        Ok(User {
+            id: "".to_string(),
+            username: "".to_string(),
+            avatar: None,
+            relations: None,
+            badges: None,
+            status: None,
+            profile: None,
+            flags: None,
+            bot: None,
+            relationship: None,
+            online: None,
+        })
+    }
+
+    async fn get_user_by_username(&self, username: &str) -> Result<User> {
+        Ok(User {
+            id: "".to_string(),
+            username: "".to_string(),
+            avatar: None,
+            relations: None,
+            badges: None,
+            status: None,
+            profile: None,
+            flags: None,
+            bot: None,
+            relationship: None,
+            online: None,
+        })
+    }
+
+    async fn get_user_id_by_bot_token(&self, token: &str) -> Result<String> {
+        todo!()
+    }
+
+    async fn get_users(&self, user_ids: Vec<&str>) -> Result<Vec<User>> {
+        todo!()
+    }
+
+    async fn get_users_as_banned_users(&self, user_ids: Vec<&str>) -> Result<Vec<BannedUser>> {
+        todo!()
+    }
+
+    async fn get_bot_users_owned_by_user_id(&self, id: &str) -> Result<Vec<User>> {
+        todo!()
+    }
+
+    async fn get_mutual_friends_ids(
+        &self,
+        user_id_a: &str,
+        user_id_b: &str,
+    ) -> Result<Vec<String>> {
+        todo!()
+    }
+
+    async fn add_user(&self, id: &str, username: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn add_bot_user(&self, id: &str, username: &str, owner_id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn delete_user(&self, id: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn update_username(&self, id: &str, new_username: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn make_user_already_in_relations_blocked(
+        &self,
+        origin_id: &str,
+        target_id: &str,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn make_user_already_in_relations_blocked_by(
+        &self,
+        target_id: &str,
+        origin_id: &str,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn make_user_not_in_relations_blocked(
+        &self,
+        origin_id: &str,
+        target_id: &str,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn make_user_not_in_relations_blocked_by(
+        &self,
+        target_id: &str,
+        origin_id: &str,
+    ) -> Result<()> {
+        todo!()
+    }
+
+    async fn apply_profile_changes(&self, id: &str, change_doc: Document) -> Result<()> {
+        todo!()
+    }
+
+    async fn remove_user_from_relations(&self, id: &str, target: &str) -> Result<()> {
+        todo!()
+    }
+
+    async fn send_friend_request(
+        &self,
+        user_id: &str,
+        target_id: &str,
+    ) -> Result<RelationshipStatus> {
+        todo!()
+    }
+
+    async fn accept_friend_request(
+        &self,
+        user_id: &str,
+        target_id: &str,
+    ) -> Result<RelationshipStatus> {
+        todo!()
+    }
+
+    async fn deny_friend_request(
+        &self,
+        user_id: &str,
+        target_id: &str,
+    ) -> Result<RelationshipStatus> {
+        todo!()
+    }
+
+    async fn remove_friend(&self, user_id: &str, target_id: &str) -> Result<RelationshipStatus> {
+        todo!()
+    }
+
+    async fn block_user(&self, user_id: &str, target_id: &str) -> Result<RelationshipStatus> {
+        todo!()
+    }
+
+    async fn unblock_user(&self, user_id: &str, target_id: &str) -> Result<RelationshipStatus> {
+        todo!()
+    }
+
+    async fn delete_all_relations(&self, id: &str) -> Result<()> {
+        todo!()
+    }
+}
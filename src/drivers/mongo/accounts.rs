@@ -0,0 +1,126 @@
+use super::MongoDB;
+use crate::entities::Subscription;
+use crate::repositories::AccountRepository;
+use crate::util::result::{Error, Result};
+use mongodb::{
+    bson::{doc, to_document},
+    options::FindOptions,
+};
+use rocket::async_trait;
+use serde::Deserialize;
+use web_push::SubscriptionInfo;
+
+#[derive(Deserialize)]
+struct AccountSessions {
+    #[serde(default)]
+    sessions: Vec<Session>,
+}
+
+#[derive(Deserialize)]
+struct Session {
+    subscription: Option<PushSubscription>,
+}
+
+#[derive(Deserialize)]
+struct PushSubscription {
+    endpoint: String,
+    p256dh: String,
+    auth: String,
+}
+
+#[async_trait]
+impl AccountRepository for MongoDB {
+    async fn get_accounts_subscriptions(
+        &self,
+        target_ids: Vec<&str>,
+    ) -> Result<Vec<SubscriptionInfo>> {
+        let cursor = self
+            .col("accounts")
+            .find(
+                doc! {
+                    "_id": {
+                        "$in": target_ids
+                    },
+                    "sessions.subscription": {
+                        "$exists": true
+                    }
+                },
+                FindOptions::builder()
+                    .projection(doc! { "sessions": 1 })
+                    .build(),
+            )
+            .await
+            .map_err(|e| Error::database("find", "accounts", e))?;
+
+        Ok(self
+            .collect::<AccountSessions, _>("accounts", cursor)
+            .await?
+            .into_iter()
+            .flat_map(|account| account.sessions)
+            .filter_map(|session| session.subscription)
+            .map(|sub| SubscriptionInfo::new(sub.endpoint, sub.p256dh, sub.auth))
+            .collect())
+    }
+
+    async fn subscribe(
+        &self,
+        account_id: &str,
+        session_id: &str,
+        subscription: Subscription,
+    ) -> Result<()> {
+        self.col("accounts")
+            .update_one(
+                doc! {
+                    "_id": account_id,
+                    "sessions.id": session_id
+                },
+                doc! {
+                    "$set": {
+                        "sessions.$.subscription": to_document(&subscription)
+                            .map_err(|e| Error::database("to_document", "accounts", e))?
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "accounts", e))?;
+        Ok(())
+    }
+
+    async fn unsubscribe(&self, account_id: &str, session_id: &str) -> Result<()> {
+        self.col("accounts")
+            .update_one(
+                doc! {
+                    "_id": account_id,
+                    "sessions.id": session_id
+                },
+                doc! {
+                    "$unset": {
+                        "sessions.$.subscription": 1
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("to_document", "accounts", e))?;
+        Ok(())
+    }
+
+    async fn delete_subscriptions(&self, account_id: &str) -> Result<()> {
+        self.col("accounts")
+            .update_one(
+                doc! {
+                    "_id": account_id
+                },
+                doc! {
+                    "$unset": {
+                        "sessions.$[].subscription": 1
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "accounts", e).for_id(account_id))?;
+        Ok(())
+    }
+}
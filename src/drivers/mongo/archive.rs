@@ -0,0 +1,187 @@
+use super::{bson_id, check_write_errors, decode, document_id, MongoDB};
+use crate::entities::{ArchiveEntity, ArchivePage, EntityKind};
+use crate::repositories::ArchiveRepository;
+use crate::util::result::{Error, Result};
+use mongodb::{
+    bson::{doc, to_document, Bson, Document},
+    options::FindOptions,
+};
+use rocket::async_trait;
+
+fn collection_of(kind: EntityKind) -> &'static str {
+    match kind {
+        EntityKind::Account => "accounts",
+        EntityKind::User => "users",
+        EntityKind::UserSettings => "user_settings",
+        EntityKind::Bot => "bots",
+        EntityKind::Server => "servers",
+        EntityKind::Member => "server_members",
+        EntityKind::Ban => "server_bans",
+        EntityKind::Channel => "channels",
+        EntityKind::Invite => "channel_invites",
+        EntityKind::Unread => "channel_unreads",
+        EntityKind::Message => "messages",
+        EntityKind::Attachment => "attachments",
+    }
+}
+
+fn decode_entity(kind: EntityKind, mut doc: Document) -> Result<ArchiveEntity> {
+    let collection = collection_of(kind);
+    Ok(match kind {
+        EntityKind::Account => ArchiveEntity::Account(doc),
+        EntityKind::User => ArchiveEntity::User(decode(collection, doc)?),
+        EntityKind::UserSettings => {
+            let id = doc.remove("_id");
+            match id {
+                Some(Bson::String(id)) => ArchiveEntity::UserSettings { id, settings: doc },
+                _ => {
+                    return Err(Error::InvalidDocument {
+                        collection,
+                        id: id.as_ref().map(bson_id).unwrap_or_default(),
+                        source: None,
+                    })
+                }
+            }
+        }
+        EntityKind::Bot => ArchiveEntity::Bot(decode(collection, doc)?),
+        EntityKind::Server => ArchiveEntity::Server(decode(collection, doc)?),
+        EntityKind::Member => ArchiveEntity::Member(decode(collection, doc)?),
+        EntityKind::Ban => ArchiveEntity::Ban(decode(collection, doc)?),
+        EntityKind::Channel => ArchiveEntity::Channel(decode(collection, doc)?),
+        EntityKind::Invite => ArchiveEntity::Invite(decode(collection, doc)?),
+        EntityKind::Unread => ArchiveEntity::Unread(decode(collection, doc)?),
+        EntityKind::Message => ArchiveEntity::Message(decode(collection, doc)?),
+        EntityKind::Attachment => ArchiveEntity::Attachment(decode(collection, doc)?),
+    })
+}
+
+/// The collection an entity is stored in, and the document it is stored as.
+fn encode_entity(entity: ArchiveEntity) -> Result<(&'static str, Document)> {
+    let (kind, document) = match entity {
+        ArchiveEntity::Account(document) => (EntityKind::Account, Ok(document)),
+        ArchiveEntity::User(user) => (EntityKind::User, to_document(&user)),
+        ArchiveEntity::UserSettings { id, settings } => {
+            let mut document = doc! { "_id": id };
+            document.extend(settings);
+            (EntityKind::UserSettings, Ok(document))
+        }
+        ArchiveEntity::Bot(bot) => (EntityKind::Bot, to_document(&bot)),
+        ArchiveEntity::Server(server) => (EntityKind::Server, to_document(&server)),
+        ArchiveEntity::Member(member) => (EntityKind::Member, to_document(&member)),
+        ArchiveEntity::Ban(ban) => (EntityKind::Ban, to_document(&ban)),
+        ArchiveEntity::Channel(channel) => (EntityKind::Channel, to_document(&channel)),
+        ArchiveEntity::Invite(invite) => (EntityKind::Invite, to_document(&invite)),
+        ArchiveEntity::Unread(unread) => (EntityKind::Unread, to_document(&unread)),
+        ArchiveEntity::Message(message) => (EntityKind::Message, to_document(&message)),
+        ArchiveEntity::Attachment(file) => (EntityKind::Attachment, to_document(&file)),
+    };
+
+    let collection = collection_of(kind);
+    let document = document.map_err(|e| Error::database("to_document", collection, e))?;
+    Ok((collection, document))
+}
+
+#[async_trait]
+impl ArchiveRepository for MongoDB {
+    async fn get_entities_page(
+        &self,
+        kind: EntityKind,
+        after: Option<Bson>,
+        limit: i64,
+    ) -> Result<ArchivePage> {
+        let collection = collection_of(kind);
+        let filter = match after {
+            Some(id) => doc! { "_id": { "$gt": id } },
+            None => doc! {},
+        };
+
+        let cursor = self
+            .col(collection)
+            .find(
+                filter,
+                FindOptions::builder()
+                    .sort(doc! { "_id": 1 })
+                    .limit(limit)
+                    .build(),
+            )
+            .await
+            .map_err(|e| Error::database("find", collection, e))?;
+
+        let (entities, last) = self
+            .collect_page(collection, cursor, limit, |doc| decode_entity(kind, doc))
+            .await?;
+
+        Ok(ArchivePage { entities, last })
+    }
+
+    async fn restore_entities(&self, entities: Vec<ArchiveEntity>) -> Result<()> {
+        let mut batches: Vec<(&'static str, Vec<Document>)> = vec![];
+        for entity in entities {
+            let (collection, document) = encode_entity(entity)?;
+            match batches.last_mut() {
+                Some((current, documents)) if *current == collection => documents.push(document),
+                _ => batches.push((collection, vec![document])),
+            }
+        }
+
+        for (collection, documents) in batches {
+            let ids: Vec<Bson> = documents
+                .iter()
+                .map(|document| {
+                    document
+                        .get("_id")
+                        .cloned()
+                        .ok_or_else(|| Error::InvalidDocument {
+                            collection,
+                            id: document_id(document),
+                            source: None,
+                        })
+                })
+                .collect::<Result<_>>()?;
+
+            let updates: Vec<Document> = documents
+                .into_iter()
+                .zip(ids.iter())
+                .map(|(document, id)| doc! { "q": { "_id": id }, "u": document, "upsert": true })
+                .collect();
+
+            let response = self
+                .revolt
+                .run_command(
+                    doc! {
+                        "update": self.collection_name(collection),
+                        "updates": updates,
+                        "ordered": false
+                    },
+                    None,
+                )
+                .await
+                .map_err(|e| Error::database("update", collection, e))?;
+
+            check_write_errors(&response, "update", collection, &ids)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collection_of, decode_entity, encode_entity};
+    use crate::entities::{ArchiveEntity, EntityKind};
+    use mongodb::bson::{from_bson, to_document};
+
+    #[test]
+    fn stored_entities_round_trip() {
+        for entity in ArchiveEntity::samples() {
+            let written = to_document(&entity).unwrap();
+            let kind: EntityKind = from_bson(written.get("kind").cloned().unwrap()).unwrap();
+
+            let (collection, document) = encode_entity(entity).unwrap();
+            assert_eq!(collection, collection_of(kind));
+
+            let read = decode_entity(kind, document).unwrap();
+            assert_eq!(written, to_document(&read).unwrap());
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use super::{decode, MongoDB};
+use crate::entities::File;
+use crate::repositories::AttachmentRepository;
+use crate::util::result::{Error, Result};
+use mongodb::bson::doc;
+use rocket::async_trait;
+
+#[async_trait]
+impl AttachmentRepository for MongoDB {
+    async fn get_attachment(&self, id: &str, tag: &str, parent_type: &str) -> Result<File> {
+        let key = format!("{}_id", parent_type);
+        if let Some(doc) = self
+            .col("attachments")
+            .find_one(
+                doc! {
+                    "_id": id,
+                    "tag": tag,
+                    key.clone(): {
+                        "$exists": false
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find_one", "attachments", e).for_id(id))?
+        {
+            let attachment = decode::<File>("attachments", doc)?;
+            Ok(attachment)
+        } else {
+            Err(Error::UnknownAttachment)
+        }
+    }
+
+    async fn link_attachment_to_parent(
+        &self,
+        id: &str,
+        parent_type: &str,
+        parent_id: &str,
+    ) -> Result<()> {
+        let key = format!("{}_id", parent_type);
+        self.col("attachments")
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$set": {
+                        key: &parent_id
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "attachments", e))?;
+        Ok(())
+    }
+
+    async fn delete_attachment(&self, id: &str) -> Result<()> {
+        self.col("attachments")
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$set": {
+                        "deleted": true
+                    }
+                },
+                None,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|e| Error::database("update_one", "attachments", e))
+    }
+
+    async fn delete_attachments(&self, ids: Vec<&str>) -> Result<()> {
+        self.col("attachments")
+            .update_many(
+                doc! {
+                    "_id": {
+                        "$in": ids
+                    }
+                },
+                doc! {
+                    "$set": {
+                        "deleted": true
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "attachments", e))?;
+        Ok(())
+    }
+
+    async fn delete_attachments_of_messages(&self, message_ids: Vec<&str>) -> Result<()> {
+        self.col("attachments")
+            .update_many(
+                doc! {
+                    "message_id": {
+                        "$in": message_ids
+                    }
+                },
+                doc! {
+                    "$set": {
+                        "deleted": true
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_many", "attachments", e))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use super::{decode, MongoDB};
+use crate::entities::Ban;
+use crate::repositories::BanRepository;
+use crate::util::result::{Error, Result};
+use mongodb::bson::doc;
+use rocket::async_trait;
+
+#[async_trait]
+impl BanRepository for MongoDB {
+    async fn delete_server_ban(&self, server_id: &str, user_id: &str) -> Result<()> {
+        self.col("server_bans")
+            .delete_one(
+                doc! {
+                    "_id.server": server_id,
+                    "_id.user": user_id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("delete_one", "server_bans", e))?;
+        Ok(())
+    }
+
+    async fn is_user_banned(&self, server_id: &str, user_id: &str) -> Result<bool> {
+        Ok(self
+            .col("server_bans")
+            .find_one(
+                doc! {
+                    "_id.server": server_id,
+                    "_id.user": user_id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find_one", "server_bans", e))?
+            .is_some())
+    }
+
+    async fn get_ban(&self, server_id: &str, user_id: &str) -> Result<Ban> {
+        let doc = self
+            .col("server_bans")
+            .find_one(
+                doc! {
+                    "_id.user": user_id,
+                    "_id.server": server_id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find_one", "server_bans", e).for_id(user_id))?
+            .ok_or_else(|| Error::UnknownBan)?;
+
+        Ok(decode::<Ban>("server_bans", doc)?)
+    }
+
+    async fn get_bans(&self, server_id: &str) -> Result<Vec<Ban>> {
+        let cursor = self
+            .col("server_bans")
+            .find(
+                doc! {
+                    "_id.server": server_id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find", "server_bans", e))?;
+
+        self.collect::<Ban, _>("server_bans", cursor).await
+    }
+
+    async fn add_server_ban(
+        &self,
+        server_id: &str,
+        user_id: &str,
+        reason: Option<&str>,
+    ) -> Result<()> {
+        let mut document = doc! {
+            "_id": {
+                "server": server_id,
+                "user": user_id
+            }
+        };
+
+        if let Some(reason) = reason {
+            document.insert("reason", reason);
+        }
+
+        self.col("server_bans")
+            .insert_one(document, None)
+            .await
+            .map_err(|e| Error::database("insert_one", "server_bans", e))?;
+        Ok(())
+    }
+}
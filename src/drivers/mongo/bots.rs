@@ -0,0 +1,80 @@
+use super::{decode, MongoDB};
+use crate::entities::Bot;
+use crate::repositories::BotRepository;
+use crate::util::result::{Error, Result};
+use mongodb::bson::{doc, to_document, Document};
+use rocket::async_trait;
+
+#[async_trait]
+impl BotRepository for MongoDB {
+    async fn get_bot_by_id(&self, id: &str) -> Result<Bot> {
+        let doc = self
+            .col("bots")
+            .find_one(doc! { "_id": id }, None)
+            .await
+            .map_err(|e| Error::database("find_one", "bots", e).for_id(id))?
+            .ok_or_else(|| Error::UnknownBot)?;
+
+        decode::<Bot>("bots", doc)
+    }
+
+    async fn get_bot_count_owned_by_user(&self, user_id: &str) -> Result<u64> {
+        Ok(self
+            .col("bots")
+            .count_documents(
+                doc! {
+                    "owner": user_id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("count_documents", "bots", e))? as u64)
+    }
+
+    async fn get_bots_owned_by_user_id(&self, id: &str) -> Result<Vec<Bot>> {
+        let cursor = self
+            .col("bots")
+            .find(
+                doc! {
+                    "owner": id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find", "bots", e))?;
+
+        self.collect::<Bot, _>("bots", cursor).await
+    }
+
+    async fn add_bot(&self, bot: &Bot) -> Result<()> {
+        self.col("bots")
+            .insert_one(
+                to_document(bot).map_err(|e| Error::database("to_document", "bots", e))?,
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("insert_one", "bots", e))?;
+        Ok(())
+    }
+
+    async fn delete_bot(&self, id: &str) -> Result<()> {
+        self.col("bots")
+            .delete_one(
+                doc! {
+                    "_id": id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("delete_one", "bots", e))?;
+        Ok(())
+    }
+
+    async fn apply_bot_changes(&self, id: &str, change_doc: Document) -> Result<()> {
+        self.col("bots")
+            .update_one(doc! { "_id": id }, change_doc, None)
+            .await
+            .map_err(|e| Error::database("update_one", "bots", e))?;
+        Ok(())
+    }
+}
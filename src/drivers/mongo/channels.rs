@@ -0,0 +1,381 @@
+use super::{decode, MongoDB};
+use crate::entities::Channel;
+use crate::repositories::ChannelRepository;
+use crate::util::result::{Error, Result};
+use mongodb::{
+    bson::{doc, to_bson, to_document, Document},
+    options::FindOptions,
+};
+use rocket::async_trait;
+
+#[async_trait]
+impl ChannelRepository for MongoDB {
+    async fn get_channel_by_id(&self, id: &str) -> Result<Channel> {
+        let doc = self
+            .col("channels")
+            .find_one(doc! { "_id": id }, None)
+            .await
+            .map_err(|e| Error::database("find_one", "channels", e).for_id(id))?
+            .ok_or_else(|| Error::UnknownChannel)?;
+
+        decode::<Channel>("channels", doc)
+    }
+
+    async fn does_channel_exist_by_nonce(&self, nonce: &str) -> Result<bool> {
+        Ok(self
+            .col("channels")
+            .find_one(
+                doc! {
+                    "nonce": nonce
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find_one", "channels", e))?
+            .is_some())
+    }
+
+    async fn remove_recipient_from_channel(
+        &self,
+        channel_id: &str,
+        recipient_id: &str,
+    ) -> Result<()> {
+        self.col("channels")
+            .update_one(
+                doc! {
+                    "_id": channel_id
+                },
+                doc! {
+                    "$pull": {
+                        "recipients": recipient_id
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "channels", e))?;
+        Ok(())
+    }
+
+    async fn update_channel_role_permissions(
+        &self,
+        channel_id: &str,
+        role: &str,
+        permissions: i32,
+    ) -> Result<()> {
+        self.col("channels")
+            .update_one(
+                doc! { "_id": channel_id },
+                doc! {
+                    "$set": {
+                        "role_permissions.".to_owned() + role: permissions
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "channels", e))?;
+        Ok(())
+    }
+
+    async fn update_channel_permissions(&self, channel_id: &str, permissions: i32) -> Result<()> {
+        self.col("channels")
+            .update_one(
+                doc! { "_id": channel_id },
+                doc! {
+                    "$set": {
+                        "permissions": permissions
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "channels", e))?;
+        Ok(())
+    }
+
+    async fn update_channel_default_permissions(
+        &self,
+        channel_id: &str,
+        default_permissions: i32,
+    ) -> Result<()> {
+        self.col("channels")
+            .update_one(
+                doc! { "_id": channel_id },
+                doc! {
+                    "$set": {
+                        "default_permissions": default_permissions
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "channels", e))?;
+        Ok(())
+    }
+
+    async fn delete_server_channels_role_permissions(
+        &self,
+        server_id: &str,
+        role_id: &str,
+    ) -> Result<()> {
+        self.col("channels")
+            .update_many(
+                doc! {
+                    "server": server_id
+                },
+                doc! {
+                    "$unset": {
+                        "role_permissions.".to_owned() + role_id: 1
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "channels", e))?;
+        Ok(())
+    }
+
+    async fn get_dm_channels_from_user(&self, user_id: &str) -> Result<Vec<Document>> {
+        let cursor = self
+            .col("channels")
+            .find(
+                doc! {
+                    "$or": [
+                        {
+                            "channel_type": "DirectMessage",
+                            "active": true
+                        },
+                        {
+                            "channel_type": "Group"
+                        }
+                    ],
+                    "recipients": user_id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find", "channels", e))?;
+
+        self.collect::<Document, _>("channels", cursor).await
+    }
+
+    async fn get_dm_channel(&self, user_a: &str, user_b: &str) -> Result<Option<Document>> {
+        let query = if user_a == user_b {
+            doc! {
+                "channel_type": "SavedMessages",
+                "user": user_a
+            }
+        } else {
+            doc! {
+                "channel_type": "DirectMessage",
+                "recipients": {
+                    "$all": [ user_a, user_b ]
+                }
+            }
+        };
+
+        Ok(self
+            .col("channels")
+            .find_one(query, None)
+            .await
+            .map_err(|e| Error::database("find_one", "channels", e))?)
+    }
+
+    async fn delete_all_channels_from_server(&self, server_id: &str) -> Result<()> {
+        self.col("channels")
+            .delete_many(
+                doc! {
+                    "server": server_id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("delete_many", "channels", e))?;
+        Ok(())
+    }
+
+    async fn add_channel(&self, channel: &Channel) -> Result<()> {
+        self.col("channels")
+            .insert_one(
+                to_document(channel).map_err(|e| Error::database("to_bson", "channels", e))?,
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("insert_one", "channels", e))?;
+        Ok(())
+    }
+
+    async fn delete_channel(&self, id: &str) -> Result<()> {
+        self.col("channels")
+            .delete_one(
+                doc! {
+                    "_id": id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("delete_one", "channels", e))?;
+        Ok(())
+    }
+
+    async fn add_recipient_to_channel(&self, channel_id: &str, recipient_id: &str) -> Result<()> {
+        self.col("channels")
+            .update_one(
+                doc! {
+                    "_id": channel_id
+                },
+                doc! {
+                    "$push": {
+                        "recipients": recipient_id
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "channels", e))?;
+        Ok(())
+    }
+
+    async fn are_users_connected_in_dms_or_group(
+        &self,
+        user_a: &str,
+        user_b: &str,
+    ) -> Result<bool> {
+        Ok(self
+            .col("channels")
+            .find_one(
+                doc! {
+                    "channel_type": {
+                        "$in": ["Group", "DirectMessage"]
+                    },
+                    "recipients": {
+                        "$all": [ user_a, user_b ]
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find_one", "channels", e))?
+            .is_some())
+    }
+
+    async fn get_sms_dms_groups_where_user_is_recipient(
+        &self,
+        channel_ids: Vec<&str>,
+        user_id: &str,
+    ) -> Result<Vec<Channel>> {
+        let cursor = self
+            .col("channels")
+            .find(
+                doc! {
+                    "$or": [
+                        {
+                            "_id": {
+                                "$in": channel_ids
+                            }
+                        },
+                        {
+                            "channel_type": "SavedMessages",
+                            "user": user_id
+                        },
+                        {
+                            "channel_type": "DirectMessage",
+                            "recipients": user_id
+                        },
+                        {
+                            "channel_type": "Group",
+                            "recipients": user_id
+                        }
+                    ]
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find", "channels", e))?;
+
+        self.collect("channels", cursor).await
+    }
+
+    async fn get_channel_ids_from_sms_dms_groups_where_user_is_recipient(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<String>> {
+        let cursor = self
+            .col("channels")
+            .find(
+                doc! {
+                    "$or": [
+                        {
+                            "channel_type": "SavedMessages",
+                            "user": user_id
+                        },
+                        {
+                            "channel_type": "DirectMessage",
+                            "recipients": user_id
+                        },
+                        {
+                            "channel_type": "Group",
+                            "recipients": user_id
+                        }
+                    ]
+                },
+                FindOptions::builder().projection(doc! { "_id": 1 }).build(),
+            )
+            .await
+            .map_err(|e| Error::database("find", "channels", e))?;
+        self.collect_ids("channels", cursor).await
+    }
+
+    async fn make_channel_inactive(&self, channel_id: &str) -> Result<()> {
+        self.col("channels")
+            .update_one(
+                doc! {
+                    "_id": channel_id
+                },
+                doc! {
+                    "$set": {
+                        "active": false
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "channels", e))?;
+        Ok(())
+    }
+
+    async fn update_channel_owner(
+        &self,
+        channel_id: &str,
+        new_owner: &str,
+        old_owner: &str,
+    ) -> Result<()> {
+        self.col("channels")
+            .update_one(
+                doc! {
+                    "_id": channel_id
+                },
+                doc! {
+                    "$set": {
+                        "owner": new_owner
+                    },
+                    "$pull": {
+                        "recipients": old_owner
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "channels", e))?;
+        Ok(())
+    }
+
+    async fn apply_channel_changes(&self, channel_id: &str, change_doc: Document) -> Result<()> {
+        self.col("channels")
+            .update_one(doc! { "_id": channel_id }, change_doc, None)
+            .await
+            .map_err(|e| Error::database("update_one", "channels", e))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,295 @@
+use super::MongoDB;
+use crate::util::result::{Error, Result};
+use log::{info, warn};
+use mongodb::bson::{doc, Bson, Document};
+use serde::Serialize;
+
+/// An index the queries in this driver rely on.
+pub struct IndexSpec {
+    pub collection: &'static str,
+    pub name: &'static str,
+    /// Fields and their direction, or `"text"` for a text index.
+    pub key: Document,
+    pub unique: bool,
+    /// Compare case-insensitively, using an English collation of strength 2.
+    pub case_insensitive: bool,
+}
+
+/// Every index the database should have.
+pub fn specs() -> Vec<IndexSpec> {
+    vec![
+        IndexSpec {
+            collection: "accounts",
+            name: "email",
+            key: doc! { "email": 1 },
+            unique: true,
+            case_insensitive: true,
+        },
+        IndexSpec {
+            collection: "accounts",
+            name: "email_normalised",
+            key: doc! { "email_normalised": 1 },
+            unique: true,
+            case_insensitive: true,
+        },
+        IndexSpec {
+            collection: "users",
+            name: "username",
+            key: doc! { "username": 1 },
+            unique: true,
+            case_insensitive: true,
+        },
+        IndexSpec {
+            collection: "messages",
+            name: "content",
+            key: doc! { "content": "text" },
+            unique: false,
+            case_insensitive: false,
+        },
+        IndexSpec {
+            collection: "messages",
+            name: "channel",
+            key: doc! { "channel": 1, "_id": 1 },
+            unique: false,
+            case_insensitive: false,
+        },
+        IndexSpec {
+            collection: "messages",
+            name: "author",
+            key: doc! { "author": 1, "_id": 1 },
+            unique: false,
+            case_insensitive: false,
+        },
+        IndexSpec {
+            collection: "channels",
+            name: "recipients",
+            key: doc! { "recipients": 1 },
+            unique: false,
+            case_insensitive: false,
+        },
+        IndexSpec {
+            collection: "server_members",
+            name: "user",
+            key: doc! { "_id.user": 1 },
+            unique: false,
+            case_insensitive: false,
+        },
+        IndexSpec {
+            collection: "bots",
+            name: "owner",
+            key: doc! { "owner": 1 },
+            unique: false,
+            case_insensitive: false,
+        },
+        IndexSpec {
+            collection: "channel_unreads",
+            name: "user",
+            key: doc! { "_id.user": 1 },
+            unique: false,
+            case_insensitive: false,
+        },
+    ]
+}
+
+/// An index whose definition no longer matches its spec.
+#[derive(Serialize, Debug, Clone)]
+pub struct IndexDrift {
+    pub collection: &'static str,
+    pub name: &'static str,
+    pub expected: Document,
+    pub actual: Document,
+}
+
+/// Differences between the declared indexes and the database.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct IndexReport {
+    /// Declared indexes that did not exist, as `collection.name`.
+    pub missing: Vec<String>,
+    /// Whether the missing indexes were created.
+    pub created: bool,
+    pub drifted: Vec<IndexDrift>,
+    /// Indexes in the database that are not declared, as `collection.name`.
+    pub unexpected: Vec<String>,
+}
+
+impl IndexSpec {
+    fn definition(&self) -> Document {
+        let mut index = doc! {
+            "key": self.key.clone(),
+            "name": self.name,
+        };
+
+        if self.unique {
+            index.insert("unique", true);
+        }
+
+        if self.case_insensitive {
+            index.insert("collation", doc! { "locale": "en", "strength": 2 });
+        }
+
+        index
+    }
+
+    /// Whether an index as reported by `listIndexes` matches this spec.
+    fn matches(&self, index: &Document) -> bool {
+        let key = match index.get_document("key") {
+            Ok(key) => key,
+            Err(_) => return false,
+        };
+
+        // Text indexes are reported by their internal key, with the indexed
+        // fields listed under `weights` instead.
+        let text: Vec<&String> = self
+            .key
+            .iter()
+            .filter(|(_, value)| value.as_str() == Some("text"))
+            .map(|(field, _)| field)
+            .collect();
+
+        let same_key = if text.is_empty() {
+            same_key(&self.key, key)
+        } else {
+            index
+                .get_document("weights")
+                .map(|weights| {
+                    weights.len() == text.len() && text.iter().all(|f| weights.contains_key(f))
+                })
+                .unwrap_or(false)
+        };
+
+        let unique = index.get_bool("unique").unwrap_or(false);
+        let case_insensitive = index
+            .get_document("collation")
+            .map(|collation| {
+                collation.get_str("locale").ok() == Some("en")
+                    && number(collation.get("strength")) == Some(2.0)
+            })
+            .unwrap_or(false);
+
+        same_key && unique == self.unique && case_insensitive == self.case_insensitive
+    }
+}
+
+/// Compare the declared indexes with the database, creating any that are
+/// missing if `create` is set. Drifted indexes are only reported, since
+/// fixing them means dropping and rebuilding the index.
+pub async fn sync(db: &MongoDB, create: bool) -> Result<IndexReport> {
+    let specs = specs();
+    let mut report = IndexReport {
+        created: create,
+        ..Default::default()
+    };
+
+    let mut collections: Vec<&'static str> = specs.iter().map(|spec| spec.collection).collect();
+    collections.sort();
+    collections.dedup();
+
+    for collection in collections {
+        let existing = list_indexes(db, collection).await?;
+        let declared: Vec<&IndexSpec> = specs
+            .iter()
+            .filter(|spec| spec.collection == collection)
+            .collect();
+
+        let mut missing = vec![];
+        for spec in &declared {
+            match existing
+                .iter()
+                .find(|index| index.get_str("name").ok() == Some(spec.name))
+            {
+                Some(index) if spec.matches(index) => {}
+                Some(index) => report.drifted.push(IndexDrift {
+                    collection,
+                    name: spec.name,
+                    expected: spec.definition(),
+                    actual: index.clone(),
+                }),
+                None => missing.push(spec.definition()),
+            }
+        }
+
+        for index in &existing {
+            let name = index.get_str("name").unwrap_or_default();
+            if name != "_id_" && !declared.iter().any(|spec| spec.name == name) {
+                report.unexpected.push(format!("{}.{}", collection, name));
+            }
+        }
+
+        for index in &missing {
+            report.missing.push(format!(
+                "{}.{}",
+                collection,
+                index.get_str("name").unwrap_or_default()
+            ));
+        }
+
+        if create && !missing.is_empty() {
+            info!("Creating {} indexes on {}.", missing.len(), collection);
+            db.revolt
+                .run_command(
+                    doc! {
+                        "createIndexes": db.collection_name(collection),
+                        "indexes": missing
+                    },
+                    None,
+                )
+                .await
+                .map_err(|e| Error::database("create_indexes", collection, e))?;
+        }
+    }
+
+    for drift in &report.drifted {
+        warn!(
+            "Index {}.{} does not match its spec, expected {} but found {}.",
+            drift.collection, drift.name, drift.expected, drift.actual
+        );
+    }
+
+    Ok(report)
+}
+
+async fn list_indexes(db: &MongoDB, collection: &'static str) -> Result<Vec<Document>> {
+    let response = db
+        .revolt
+        .run_command(doc! { "listIndexes": db.collection_name(collection) }, None)
+        .await
+        .map_err(|e| Error::database("list_indexes", collection, e))?;
+
+    // A collection has few enough indexes to fit in the first batch.
+    let batch = response
+        .get_document("cursor")
+        .and_then(|cursor| cursor.get_array("firstBatch"))
+        .map_err(|_| Error::DatabaseError {
+            operation: "list_indexes",
+            with: collection,
+            id: None,
+            source: None,
+        })?;
+
+    Ok(batch
+        .iter()
+        .filter_map(|index| index.as_document().cloned())
+        .collect())
+}
+
+/// Index keys compare equal regardless of the numeric type the server used.
+fn same_key(expected: &Document, actual: &Document) -> bool {
+    expected.len() == actual.len()
+        && expected.iter().zip(actual.iter()).all(|((a, x), (b, y))| {
+            a == b
+                && (x == y
+                    || match (number(Some(x)), number(Some(y))) {
+                        (Some(x), Some(y)) => x == y,
+                        _ => false,
+                    })
+        })
+}
+
+fn number(value: Option<&Bson>) -> Option<f64> {
+    match value? {
+        Bson::Int32(value) => Some(*value as f64),
+        Bson::Int64(value) => Some(*value as f64),
+        Bson::Double(value) => Some(*value),
+        _ => None,
+    }
+}
@@ -0,0 +1,73 @@
+use super::{decode, MongoDB};
+use crate::entities::Invite;
+use crate::repositories::InviteRepository;
+use crate::util::result::{Error, Result};
+use mongodb::bson::{doc, to_bson, to_document};
+use rocket::async_trait;
+
+#[async_trait]
+impl InviteRepository for MongoDB {
+    async fn delete_invites_associated_to_channel(&self, id: &str) -> Result<()> {
+        self.col("channel_invites")
+            .delete_many(
+                doc! {
+                    "channel": id
+                },
+                None,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|e| Error::database("delete_many", "channel_invites", e))
+    }
+
+    async fn get_invite_by_id(&self, id: &str) -> Result<Invite> {
+        let doc = self
+            .col("channel_invites")
+            .find_one(doc! { "_id": id }, None)
+            .await
+            .map_err(|e| Error::database("find_one", "channel_invites", e).for_id(id))?
+            .ok_or_else(|| Error::UnknownInvite)?;
+
+        decode::<Invite>("channel_invites", doc)
+    }
+
+    async fn add_invite(&self, invite: &Invite) -> Result<()> {
+        self.col("channel_invites")
+            .insert_one(
+                to_document(invite)
+                    .map_err(|e| Error::database("to_bson", "channel_invites", e))?,
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("insert_one", "channel_invites", e))?;
+        Ok(())
+    }
+
+    async fn delete_invite(&self, id: &str) -> Result<()> {
+        self.col("channel_invites")
+            .delete_one(
+                doc! {
+                    "_id": id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("delete_one", "channel_invites", e))?;
+        Ok(())
+    }
+
+    async fn get_invites_of_server(&self, server_id: &str) -> Result<Vec<Invite>> {
+        let cursor = self
+            .col("channel_invites")
+            .find(
+                doc! {
+                    "server": server_id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find", "channel_invites", e))?;
+
+        self.collect::<Invite, _>("channel_invites", cursor).await
+    }
+}
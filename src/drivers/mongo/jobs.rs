@@ -0,0 +1,98 @@
+use super::{decode, MongoDB};
+use crate::entities::Job;
+use crate::repositories::JobRepository;
+use crate::util::result::{Error, Result};
+use chrono::{Duration, Utc};
+use mongodb::bson::{doc, to_document};
+use rocket::async_trait;
+
+#[async_trait]
+impl JobRepository for MongoDB {
+    async fn get_job(&self, id: &str) -> Result<Job> {
+        let doc = self
+            .col("jobs")
+            .find_one(doc! { "_id": id }, None)
+            .await
+            .map_err(|e| Error::database("find_one", "jobs", e).for_id(id))?
+            .ok_or_else(|| Error::UnknownJob)?;
+
+        decode::<Job>("jobs", doc)
+    }
+
+    async fn get_jobs(&self) -> Result<Vec<Job>> {
+        let cursor = self
+            .col("jobs")
+            .find(doc! {}, None)
+            .await
+            .map_err(|e| Error::database("find", "jobs", e))?;
+
+        self.collect::<Job, _>("jobs", cursor).await
+    }
+
+    async fn add_job(&self, job: &Job) -> Result<()> {
+        self.col("jobs")
+            .insert_one(
+                to_document(job).map_err(|e| Error::database("to_document", "jobs", e))?,
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("insert_one", "jobs", e).for_id(&job.id))?;
+        Ok(())
+    }
+
+    async fn claim_job(&self, id: &str, owner: &str, lease: Duration) -> Result<bool> {
+        let now = Utc::now();
+        let result = self
+            .col("jobs")
+            .update_one(
+                doc! {
+                    "_id": id,
+                    "$or": [
+                        { "owner": { "$exists": false } },
+                        { "owner": owner },
+                        { "expires_at": { "$lt": now } }
+                    ]
+                },
+                doc! { "$set": { "owner": owner, "expires_at": now + lease } },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "jobs", e).for_id(id))?;
+
+        Ok(result.matched_count > 0)
+    }
+
+    async fn release_job(&self, id: &str, owner: &str) -> Result<()> {
+        self.col("jobs")
+            .update_one(
+                doc! { "_id": id, "owner": owner },
+                doc! { "$unset": { "owner": 1, "expires_at": 1 } },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "jobs", e).for_id(id))?;
+        Ok(())
+    }
+
+    async fn set_job_step(&self, id: &str, owner: &str, step: i32) -> Result<bool> {
+        let result = self
+            .col("jobs")
+            .update_one(
+                doc! { "_id": id, "owner": owner },
+                doc! { "$set": { "step": step } },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "jobs", e).for_id(id))?;
+
+        Ok(result.matched_count > 0)
+    }
+
+    async fn delete_job(&self, id: &str) -> Result<()> {
+        self.col("jobs")
+            .delete_one(doc! { "_id": id }, None)
+            .await
+            .map_err(|e| Error::database("delete_one", "jobs", e).for_id(id))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+use super::{decode, MongoDB};
+use crate::entities::Member;
+use crate::repositories::MemberRepository;
+use crate::util::result::{Error, Result};
+use mongodb::bson::{doc, Document};
+use rocket::async_trait;
+
+#[async_trait]
+impl MemberRepository for MongoDB {
+    async fn get_server_member(&self, server_id: &str, user_id: &str) -> Result<Member> {
+        let doc = self
+            .col("server_members")
+            .find_one(
+                doc! {
+                    "_id.user": user_id,
+                    "_id.server": server_id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find_one", "server_members", e).for_id(user_id))?
+            .ok_or_else(|| Error::UnknownMember)?;
+
+        Ok(decode::<Member>("server_members", doc)?)
+    }
+
+    async fn get_server_members(&self, server_id: &str) -> Result<Vec<Member>> {
+        let cursor = self
+            .col("server_members")
+            .find(
+                doc! {
+                    "_id.server": server_id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find", "server_members", e))?;
+
+        self.collect::<Member, _>("server_members", cursor).await
+    }
+
+    async fn add_server_member(&self, server_id: &str, user_id: &str) -> Result<()> {
+        self.col("server_members")
+            .insert_one(
+                doc! {
+                    "_id": {
+                        "server": server_id,
+                        "user": user_id
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("insert_one", "server_members", e))?;
+        Ok(())
+    }
+
+    async fn delete_server_member(&self, server_id: &str, user_id: &str) -> Result<i64> {
+        let delete_count = self
+            .col("server_members")
+            .delete_one(
+                doc! {
+                    "_id": {
+                        "server": server_id,
+                        "user": user_id
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("delete_one", "server_members", e))?
+            .deleted_count;
+        Ok(delete_count)
+    }
+
+    async fn get_server_member_count(&self, server_id: &str) -> Result<i64> {
+        Ok(self
+            .col("server_members")
+            .count_documents(
+                doc! {
+                    "_id.server": server_id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("count_documents", "server_members", e))?)
+    }
+
+    async fn get_users_memberships(&self, user_id: &str) -> Result<Vec<Member>> {
+        let cursor = self
+            .col("server_members")
+            .find(
+                doc! {
+                    "_id.user": user_id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find", "server_members", e))?;
+
+        self.collect::<Member, _>("server_members", cursor).await
+    }
+
+    async fn is_user_member_in_one_of_servers(
+        &self,
+        user_id: &str,
+        server_ids: Vec<&str>,
+    ) -> Result<bool> {
+        Ok(self
+            .col("server_members")
+            .find_one(
+                doc! {
+                    "_id.user": user_id,
+                    "_id.server": {
+                        "$in": server_ids
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find_one", "server_members", e))?
+            .is_some())
+    }
+
+    async fn apply_server_member_changes(
+        &self,
+        server_id: &str,
+        user_id: &str,
+        change_doc: Document,
+    ) -> Result<()> {
+        self.col("server_members")
+            .update_one(
+                doc! { "_id.server": server_id, "_id.user": user_id },
+                change_doc,
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "server_members", e))?;
+        Ok(())
+    }
+
+    async fn delete_role_from_server_members(&self, server_id: &str, role_id: &str) -> Result<()> {
+        self.col("server_members")
+            .update_many(
+                doc! {
+                    "_id.server": server_id
+                },
+                doc! {
+                    "$pull": {
+                        "roles": role_id
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_many", "server_members", e))?;
+        Ok(())
+    }
+
+    async fn get_server_memberships_by_ids(
+        &self,
+        user_id: &str,
+        server_ids: Vec<&str>,
+    ) -> Result<Vec<Member>> {
+        let cursor = self
+            .col("server_members")
+            .find(
+                doc! {
+                    "_id.user": user_id,
+                    "_id.server": {
+                        "$in": server_ids
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find", "server_members", e))?;
+
+        self.collect::<Member, _>("server_members", cursor).await
+    }
+}
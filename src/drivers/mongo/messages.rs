@@ -0,0 +1,494 @@
+use super::{bson_id, decode, MongoDB};
+use crate::entities::microservice::january::Embed;
+use crate::entities::{Message, MessageFilter, MessagePage, Sort};
+use crate::repositories::{AttachmentRepository, MessageRepository};
+use crate::util::result::{Error, Result};
+use chrono::{Duration, Utc};
+use mongodb::{
+    bson::{doc, to_bson, to_document, Document},
+    options::{FindOneOptions, FindOptions},
+};
+use rocket::async_trait;
+use ulid::Ulid;
+
+impl MongoDB {
+    /// Hard-deletes the given messages, marks their attachments as deleted
+    /// and drops them from any mentions in unreads matching `unreads_filter`.
+    async fn remove_messages(
+        &self,
+        message_ids: &Vec<String>,
+        mut unreads_filter: Document,
+    ) -> Result<()> {
+        if message_ids.is_empty() {
+            return Ok(());
+        }
+
+        self.delete_attachments_of_messages(message_ids.iter().map(|x| x.as_str()).collect())
+            .await?;
+
+        unreads_filter.insert("mentions", doc! { "$in": message_ids });
+        self.col("channel_unreads")
+            .update_many(
+                unreads_filter,
+                doc! {
+                    "$pull": {
+                        "mentions": {
+                            "$in": message_ids
+                        }
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_many", "channel_unreads", e))?;
+
+        self.col("messages")
+            .delete_many(
+                doc! {
+                    "_id": {
+                        "$in": message_ids
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("delete_many", "messages", e))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MessageRepository for MongoDB {
+    async fn get_message_by_id(&self, id: &str) -> Result<Message> {
+        let doc = self
+            .col("messages")
+            .find_one(
+                doc! {
+                    "_id": id,
+                    "deleted": { "$exists": false }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find_one", "messages", e).for_id(id))?
+            .ok_or_else(|| Error::UnknownMessage)?;
+
+        decode::<Message>("messages", doc)
+    }
+
+    async fn set_message_updates(&self, message_id: &str, set_doc: Document) -> Result<()> {
+        self.col("messages")
+            .update_one(
+                doc! {
+                    "_id": message_id
+                },
+                doc! {
+                    "$set": set_doc
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "messages", e))?;
+        Ok(())
+    }
+
+    async fn get_ids_from_messages_with_attachments(
+        &self,
+        channel_id: &str,
+    ) -> Result<Vec<String>> {
+        let cursor = self
+            .col("messages")
+            .find(
+                doc! {
+                    "channel": channel_id,
+                    "attachments": {
+                        "$exists": 1
+                    }
+                },
+                FindOptions::builder().projection(doc! { "_id": 1 }).build(),
+            )
+            .await
+            .map_err(|e| Error::database("fetch_many", "messages", e))?;
+
+        self.collect_ids("messages", cursor).await
+    }
+
+    async fn delete_messages_from_channel(&self, channel_id: &str) -> Result<()> {
+        self.col("messages")
+            .delete_many(
+                doc! {
+                    "channel": channel_id
+                },
+                None,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|e| Error::database("delete_many", "messages", e))
+    }
+
+    async fn add_message(&self, message: &Message) -> Result<()> {
+        self.col("messages")
+            .insert_one(
+                to_document(message).map_err(|e| Error::database("to_document", "messages", e))?,
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("insert_one", "messages", e))?;
+        Ok(())
+    }
+
+    async fn add_embeds_to_message(&self, message_id: &str, embeds: &Vec<Embed>) -> Result<()> {
+        let bson = to_bson(embeds).map_err(|e| Error::database("update_one", "messages", e))?;
+        self.col("messages")
+            .update_one(
+                doc! {
+                    "_id": message_id
+                },
+                doc! {
+                    "$set": {
+                        "embeds": bson
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "messages", e))?;
+        Ok(())
+    }
+
+    async fn delete_message(&self, message_id: &str) -> Result<()> {
+        self.col("messages")
+            .delete_one(
+                doc! {
+                    "_id": message_id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("delete_one", "messages", e))?;
+        Ok(())
+    }
+
+    async fn get_messages_by_ids_and_channel(
+        &self,
+        message_ids: Vec<&str>,
+        channel_id: &str,
+    ) -> Result<Vec<Message>> {
+        let cursor = self
+            .col("messages")
+            .find(
+                doc! {
+                    "_id": {
+                        "$in": message_ids
+                    },
+                    "channel": channel_id,
+                    "deleted": {
+                        "$exists": false
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find", "messages", e))?;
+
+        self.collect::<Message, _>("messages", cursor).await
+    }
+
+    async fn search_messages(
+        &self,
+        channel_id: &str,
+        search: &str,
+        options_before: Option<&str>,
+        options_after: Option<&str>,
+        limit: i64,
+        sort: Sort,
+    ) -> Result<Vec<Message>> {
+        let mut filter = doc! {
+            "channel": channel_id,
+            "deleted": {
+                "$exists": false
+            },
+            "$text": {
+                "$search": search
+            }
+        };
+
+        if let Some(doc) = match (options_before, options_after) {
+            (Some(before), Some(after)) => Some(doc! {
+                "lt": before,
+                "gt": after
+            }),
+            (Some(before), _) => Some(doc! {
+                "lt": before
+            }),
+            (_, Some(after)) => Some(doc! {
+                "gt": after
+            }),
+            _ => None,
+        } {
+            filter.insert("_id", doc);
+        }
+
+        let cursor = self
+            .col("messages")
+            .find(
+                filter,
+                FindOptions::builder()
+                    .projection(if let Sort::Relevance = sort {
+                        doc! {
+                            "score": {
+                                "$meta": "textScore"
+                            }
+                        }
+                    } else {
+                        doc! {}
+                    })
+                    .limit(limit)
+                    .sort(match sort {
+                        Sort::Relevance => doc! {
+                            "score": {
+                                "$meta": "textScore"
+                            }
+                        },
+                        Sort::Latest => doc! {
+                            "_id": -1
+                        },
+                        Sort::Oldest => doc! {
+                            "_id": 1
+                        },
+                    })
+                    .build(),
+            )
+            .await
+            .map_err(|e| Error::database("find", "messages", e))?;
+        self.collect::<Message, _>("messages", cursor).await
+    }
+
+    async fn does_message_exist_by_nonce(&self, nonce: &str) -> Result<bool> {
+        Ok(self
+            .col("messages")
+            .find_one(
+                doc! {
+                    "nonce": nonce
+                },
+                FindOneOptions::builder()
+                    .projection(doc! { "_id": 1 })
+                    .build(),
+            )
+            .await
+            .map_err(|e| Error::database("find_one", "messages", e))?
+            .is_some())
+    }
+
+    async fn delete_messages_bulk(
+        &self,
+        channel_id: &str,
+        filter: &MessageFilter,
+    ) -> Result<Vec<String>> {
+        // Refuse to wipe an entire channel by accident.
+        if filter.is_empty() {
+            return Err(Error::InvalidOperation);
+        }
+
+        let mut conditions = vec![];
+        if let Some(ids) = &filter.ids {
+            conditions.push(doc! { "_id": { "$in": ids.clone() } });
+        }
+
+        if let Some(before) = &filter.before {
+            conditions.push(doc! { "_id": { "$lt": before } });
+        }
+
+        if let Some(after) = &filter.after {
+            conditions.push(doc! { "_id": { "$gt": after } });
+        }
+
+        // Message IDs are ULIDs, so a time window translates into an ID range.
+        if let Some(since) = &filter.since {
+            let lower = Ulid::from_parts(since.timestamp_millis() as u64, 0).to_string();
+            conditions.push(doc! { "_id": { "$gte": lower } });
+        }
+
+        if let Some(until) = &filter.until {
+            let upper = Ulid::from_parts(until.timestamp_millis() as u64 + 1, 0).to_string();
+            conditions.push(doc! { "_id": { "$lt": upper } });
+        }
+
+        if let Some(author) = &filter.author {
+            conditions.push(doc! { "author": author });
+        }
+
+        let cursor = self
+            .col("messages")
+            .find(
+                doc! {
+                    "channel": channel_id,
+                    "$and": conditions
+                },
+                FindOptions::builder().projection(doc! { "_id": 1 }).build(),
+            )
+            .await
+            .map_err(|e| Error::database("find", "messages", e))?;
+
+        let message_ids = self.collect_ids("messages", cursor).await?;
+
+        self.remove_messages(&message_ids, doc! { "_id.channel": channel_id })
+            .await?;
+        Ok(message_ids)
+    }
+
+    async fn soft_delete_message(&self, message_id: &str, deleted_by: &str) -> Result<()> {
+        let result = self
+            .col("messages")
+            .update_one(
+                doc! {
+                    "_id": message_id,
+                    "deleted": {
+                        "$exists": false
+                    }
+                },
+                doc! {
+                    "$set": {
+                        "deleted": {
+                            "at": Utc::now(),
+                            "by": deleted_by
+                        }
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "messages", e))?;
+
+        if result.matched_count == 0 {
+            Err(Error::UnknownMessage)
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn restore_message(&self, message_id: &str, grace_period: Duration) -> Result<()> {
+        let result = self
+            .col("messages")
+            .update_one(
+                doc! {
+                    "_id": message_id,
+                    "deleted.at": {
+                        "$gte": Utc::now() - grace_period
+                    }
+                },
+                doc! {
+                    "$unset": {
+                        "deleted": 1
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "messages", e))?;
+
+        if result.matched_count == 0 {
+            Err(Error::UnknownMessage)
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn get_soft_deleted_messages(&self, channel_id: &str) -> Result<Vec<Message>> {
+        let cursor = self
+            .col("messages")
+            .find(
+                doc! {
+                    "channel": channel_id,
+                    "deleted": {
+                        "$exists": true
+                    }
+                },
+                FindOptions::builder().sort(doc! { "_id": -1 }).build(),
+            )
+            .await
+            .map_err(|e| Error::database("find", "messages", e))?;
+
+        self.collect::<Message, _>("messages", cursor).await
+    }
+
+    async fn purge_soft_deleted_messages(&self, grace_period: Duration) -> Result<Vec<String>> {
+        let cursor = self
+            .col("messages")
+            .find(
+                doc! {
+                    "deleted.at": {
+                        "$lt": Utc::now() - grace_period
+                    }
+                },
+                FindOptions::builder().projection(doc! { "_id": 1 }).build(),
+            )
+            .await
+            .map_err(|e| Error::database("find", "messages", e))?;
+
+        let message_ids = self.collect_ids("messages", cursor).await?;
+
+        self.remove_messages(&message_ids, doc! {}).await?;
+        Ok(message_ids)
+    }
+
+    async fn get_messages_by_author(
+        &self,
+        author: &str,
+        after: Option<&str>,
+        limit: i64,
+    ) -> Result<MessagePage> {
+        let mut filter = doc! {
+            "author": author
+        };
+
+        if let Some(after) = after {
+            filter.insert("_id", doc! { "$gt": after });
+        }
+
+        let cursor = self
+            .col("messages")
+            .find(
+                filter,
+                FindOptions::builder()
+                    .sort(doc! { "_id": 1 })
+                    .limit(limit)
+                    .build(),
+            )
+            .await
+            .map_err(|e| Error::database("find", "messages", e))?;
+
+        let (messages, last) = self
+            .collect_page("messages", cursor, limit, |doc| decode("messages", doc))
+            .await?;
+
+        Ok(MessagePage {
+            messages,
+            last: last.as_ref().map(bson_id),
+        })
+    }
+
+    async fn scrub_messages(&self, message_ids: Vec<&str>) -> Result<()> {
+        self.col("messages")
+            .update_many(
+                doc! {
+                    "_id": {
+                        "$in": message_ids
+                    }
+                },
+                doc! {
+                    "$set": {
+                        "content": ""
+                    },
+                    "$unset": {
+                        "attachments": 1,
+                        "embeds": 1
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_many", "messages", e))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use crate::drivers::mongo::{check_write_errors, document_id, MongoDB};
+use crate::util::result::{Error, Result};
+use futures::StreamExt;
+use log::info;
+use mongodb::{
+    bson::{doc, Bson, Document},
+    options::{FindOptions, UpdateOptions},
+};
+
+/// Documents rewritten per round trip.
+const BATCH_SIZE: i64 = 1000;
+
+/// Rewrite every document in `collection` matching `filter`, in `_id` order
+/// and `BATCH_SIZE` at a time. `update` turns each projected document into
+/// its update statement.
+///
+/// Progress is saved in the `migrations` collection after every batch, so if
+/// the process stops, running `migration` again picks up where it left off.
+pub async fn update_in_batches<F>(
+    db: &MongoDB,
+    migration: i32,
+    collection: &'static str,
+    filter: Document,
+    projection: Document,
+    update: F,
+) -> Result<()>
+where
+    F: Fn(&Document) -> Result<Document> + Send + Sync,
+{
+    let mut last_id = checkpoint(db, migration).await?;
+    let mut total = 0;
+
+    loop {
+        let query = match &last_id {
+            Some(id) => doc! { "$and": [ filter.clone(), { "_id": { "$gt": id.clone() } } ] },
+            None => filter.clone(),
+        };
+
+        let mut cursor = db
+            .col(collection)
+            .find(
+                query,
+                FindOptions::builder()
+                    .sort(doc! { "_id": 1 })
+                    .limit(BATCH_SIZE)
+                    .projection(projection.clone())
+                    .build(),
+            )
+            .await
+            .map_err(|e| Error::database("find", collection, e))?;
+
+        let mut ids = vec![];
+        let mut updates = vec![];
+        while let Some(result) = cursor.next().await {
+            let doc = result.map_err(|e| Error::database("next", collection, e))?;
+            let id = doc
+                .get("_id")
+                .cloned()
+                .ok_or_else(|| Error::InvalidDocument {
+                    collection,
+                    id: document_id(&doc),
+                    source: None,
+                })?;
+
+            updates.push(doc! { "q": { "_id": id.clone() }, "u": update(&doc)? });
+            ids.push(id);
+        }
+
+        let id = match ids.last() {
+            Some(id) => id.clone(),
+            None => break,
+        };
+
+        total += updates.len();
+        let response = db
+            .revolt
+            .run_command(
+                doc! {
+                    "update": db.collection_name(collection),
+                    "updates": updates,
+                    "ordered": true
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update", collection, e))?;
+
+        check_write_errors(&response, "update", collection, &ids)?;
+
+        save_checkpoint(db, migration, &id).await?;
+        last_id = Some(id);
+        info!("Updated {} documents in {}.", total, collection);
+    }
+
+    clear_checkpoint(db, migration).await
+}
+
+/// `_id` of the document holding a migration's progress.
+fn checkpoint_id(migration: i32) -> Document {
+    doc! { "checkpoint": migration }
+}
+
+async fn checkpoint(db: &MongoDB, migration: i32) -> Result<Option<Bson>> {
+    let checkpoint = db
+        .col("migrations")
+        .find_one(doc! { "_id": checkpoint_id(migration) }, None)
+        .await
+        .map_err(|e| Error::database("find_one", "migrations", e))?;
+
+    let last_id = checkpoint.and_then(|doc| doc.get("last_id").cloned());
+    if let Some(id) = &last_id {
+        info!("Resuming migration {} after {}.", migration, id);
+    }
+
+    Ok(last_id)
+}
+
+async fn save_checkpoint(db: &MongoDB, migration: i32, last_id: &Bson) -> Result<()> {
+    db.col("migrations")
+        .update_one(
+            doc! { "_id": checkpoint_id(migration) },
+            doc! { "$set": { "last_id": last_id.clone() } },
+            UpdateOptions::builder().upsert(true).build(),
+        )
+        .await
+        .map_err(|e| Error::database("update_one", "migrations", e))?;
+
+    Ok(())
+}
+
+async fn clear_checkpoint(db: &MongoDB, migration: i32) -> Result<()> {
+    db.col("migrations")
+        .delete_one(doc! { "_id": checkpoint_id(migration) }, None)
+        .await
+        .map_err(|e| Error::database("delete_one", "migrations", e))?;
+
+    Ok(())
+}
@@ -1,157 +1,98 @@
-use super::scripts::LATEST_REVISION;
+use super::revisions::registry;
+use super::scripts;
+use crate::drivers::mongo::MongoDB;
+use crate::util::result::{Error, Result};
 
 use log::info;
-use mongodb::bson::doc;
 use mongodb::options::CreateCollectionOptions;
-use mongodb::Database;
 
-pub async fn create_database(db: &Database) {
+pub async fn create_database(db: &MongoDB) -> Result<()> {
     info!("Creating database.");
 
-    db.create_collection("accounts", None)
+    db.revolt
+        .create_collection(&db.collection_name("accounts"), None)
         .await
-        .expect("Failed to create accounts collection.");
+        .map_err(|e| Error::database("create_collection", "accounts", e))?;
 
-    db.create_collection("users", None)
+    db.revolt
+        .create_collection(&db.collection_name("users"), None)
         .await
-        .expect("Failed to create users collection.");
+        .map_err(|e| Error::database("create_collection", "users", e))?;
 
-    db.create_collection("channels", None)
+    db.revolt
+        .create_collection(&db.collection_name("channels"), None)
         .await
-        .expect("Failed to create channels collection.");
+        .map_err(|e| Error::database("create_collection", "channels", e))?;
 
-    db.create_collection("messages", None)
+    db.revolt
+        .create_collection(&db.collection_name("messages"), None)
         .await
-        .expect("Failed to create messages collection.");
+        .map_err(|e| Error::database("create_collection", "messages", e))?;
 
-    db.create_collection("servers", None)
+    db.revolt
+        .create_collection(&db.collection_name("servers"), None)
         .await
-        .expect("Failed to create servers collection.");
+        .map_err(|e| Error::database("create_collection", "servers", e))?;
 
-    db.create_collection("server_members", None)
+    db.revolt
+        .create_collection(&db.collection_name("server_members"), None)
         .await
-        .expect("Failed to create server_members collection.");
+        .map_err(|e| Error::database("create_collection", "server_members", e))?;
 
-    db.create_collection("server_bans", None)
+    db.revolt
+        .create_collection(&db.collection_name("server_bans"), None)
         .await
-        .expect("Failed to create server_bans collection.");
+        .map_err(|e| Error::database("create_collection", "server_bans", e))?;
 
-    db.create_collection("channel_invites", None)
+    db.revolt
+        .create_collection(&db.collection_name("channel_invites"), None)
         .await
-        .expect("Failed to create channel_invites collection.");
+        .map_err(|e| Error::database("create_collection", "channel_invites", e))?;
 
-    db.create_collection("channel_unreads", None)
+    db.revolt
+        .create_collection(&db.collection_name("channel_unreads"), None)
         .await
-        .expect("Failed to create channel_unreads collection.");
+        .map_err(|e| Error::database("create_collection", "channel_unreads", e))?;
 
-    db.create_collection("migrations", None)
+    // The migrations collection already exists, holding the migration lock.
+
+    db.revolt
+        .create_collection(&db.collection_name("attachments"), None)
         .await
-        .expect("Failed to create migrations collection.");
+        .map_err(|e| Error::database("create_collection", "attachments", e))?;
 
-    db.create_collection("attachments", None)
+    db.revolt
+        .create_collection(&db.collection_name("user_settings"), None)
         .await
-        .expect("Failed to create attachments collection.");
+        .map_err(|e| Error::database("create_collection", "user_settings", e))?;
 
-    db.create_collection("user_settings", None)
+    db.revolt
+        .create_collection(&db.collection_name("bots"), None)
         .await
-        .expect("Failed to create user_settings collection.");
+        .map_err(|e| Error::database("create_collection", "bots", e))?;
 
-    db.create_collection("bots", None)
+    db.revolt
+        .create_collection(&db.collection_name("jobs"), None)
         .await
-        .expect("Failed to create bots collection.");
-
-    db.create_collection(
-        "pubsub",
-        CreateCollectionOptions::builder()
-            .capped(true)
-            .size(1_000_000)
-            .build(),
-    )
-    .await
-    .expect("Failed to create pubsub collection.");
-
-    db.run_command(
-        doc! {
-            "createIndexes": "accounts",
-            "indexes": [
-                {
-                    "key": {
-                        "email": 1
-                    },
-                    "name": "email",
-                    "unique": true,
-                    "collation": {
-                        "locale": "en",
-                        "strength": 2
-                    }
-                },
-                {
-                    "key": {
-                        "email_normalised": 1
-                    },
-                    "name": "email_normalised",
-                    "unique": true,
-                    "collation": {
-                        "locale": "en",
-                        "strength": 2
-                    }
-                }
-            ]
-        },
-        None,
-    )
-    .await
-    .expect("Failed to create account index.");
-
-    db.run_command(
-        doc! {
-            "createIndexes": "users",
-            "indexes": [
-                {
-                    "key": {
-                        "username": 1
-                    },
-                    "name": "username",
-                    "unique": true,
-                    "collation": {
-                        "locale": "en",
-                        "strength": 2
-                    }
-                }
-            ]
-        },
-        None,
-    )
-    .await
-    .expect("Failed to create username index.");
-
-    db.run_command(
-        doc! {
-            "createIndexes": "messages",
-            "indexes": [
-                {
-                    "key": {
-                        "content": "text"
-                    },
-                    "name": "content"
-                }
-            ]
-        },
-        None,
-    )
-    .await
-    .expect("Failed to create message index.");
-
-    db.collection("migrations")
-        .insert_one(
-            doc! {
-                "_id": 0,
-                "revision": LATEST_REVISION
-            },
-            None,
+        .map_err(|e| Error::database("create_collection", "jobs", e))?;
+
+    db.revolt
+        .create_collection(
+            &db.collection_name("pubsub"),
+            CreateCollectionOptions::builder()
+                .capped(true)
+                .size(1_000_000)
+                .build(),
         )
         .await
-        .expect("Failed to save migration info.");
+        .map_err(|e| Error::database("create_collection", "pubsub", e))?;
+
+    // A fresh database already has everything the migrations would add.
+    // Indexes are created by `indexes::sync` once this returns.
+    for migration in registry() {
+        scripts::record(db, migration.as_ref()).await?;
+    }
 
     info!("Created database.");
+    Ok(())
 }
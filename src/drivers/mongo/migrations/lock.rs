@@ -0,0 +1,169 @@
+use crate::drivers::mongo::MongoDB;
+use crate::util::result::{Error, Result};
+use async_std::task::sleep;
+use chrono::Utc;
+use futures::future::{select, Either};
+use futures::{pin_mut, Future};
+use log::{info, warn};
+use mongodb::bson::doc;
+use std::time::{Duration, Instant};
+use ulid::Ulid;
+
+/// `_id` of the lock document in the `migrations` collection.
+const LOCK_ID: &str = "lock";
+
+/// How long a lock stays valid without a heartbeat. A process that crashes
+/// while migrating blocks others for at most this long.
+const LEASE_SECONDS: i64 = 30;
+
+/// How often the holder renews its lease.
+const HEARTBEAT: Duration = Duration::from_secs(10);
+
+/// How often a waiting process retries.
+const RETRY: Duration = Duration::from_secs(2);
+
+/// Lease on the right to migrate, held by at most one process at a time.
+pub struct MigrationLock {
+    owner: String,
+}
+
+impl MigrationLock {
+    /// Take the lock, waiting up to `wait` for another holder to release it
+    /// or for its lease to expire.
+    pub async fn acquire(db: &MongoDB, wait: Duration) -> Result<MigrationLock> {
+        let owner = format!("{}-{}", std::process::id(), Ulid::new());
+        let started = Instant::now();
+
+        loop {
+            let holder = match try_acquire(db, &owner).await? {
+                Some(holder) => holder,
+                None => return Ok(MigrationLock { owner }),
+            };
+
+            if started.elapsed() >= wait {
+                return Err(Error::MigrationLocked { owner: holder });
+            }
+
+            info!("Waiting for migration lock held by {}.", holder);
+            sleep(RETRY).await;
+        }
+    }
+
+    /// Run `task` while keeping the lease alive, abandoning it if the lock
+    /// is lost so two processes never migrate at once.
+    pub async fn hold<F, T>(&self, db: &MongoDB, task: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        let heartbeat = self.heartbeat(db);
+        pin_mut!(task);
+        pin_mut!(heartbeat);
+
+        match select(task, heartbeat).await {
+            Either::Left((result, _)) => result,
+            Either::Right((error, _)) => Err(error),
+        }
+    }
+
+    pub async fn release(self, db: &MongoDB) -> Result<()> {
+        db.col("migrations")
+            .delete_one(doc! { "_id": LOCK_ID, "owner": &self.owner }, None)
+            .await
+            .map_err(|e| Error::database("delete_one", "migrations", e))?;
+
+        Ok(())
+    }
+
+    /// Renew the lease until that fails, returning why.
+    async fn heartbeat(&self, db: &MongoDB) -> Error {
+        loop {
+            sleep(HEARTBEAT).await;
+
+            let result = db
+                .col("migrations")
+                .update_one(
+                    doc! { "_id": LOCK_ID, "owner": &self.owner },
+                    doc! { "$set": { "expires_at": expiry() } },
+                    None,
+                )
+                .await;
+
+            match result {
+                Ok(result) if result.matched_count > 0 => {}
+                Ok(_) => {
+                    warn!("Lost the migration lock, stopping migration.");
+                    return Error::MigrationLocked {
+                        owner: holder(db).await.ok().flatten().unwrap_or_default(),
+                    };
+                }
+                Err(error) => return Error::database("update_one", "migrations", error),
+            }
+        }
+    }
+}
+
+/// Remove the lock whoever holds it, for recovering by hand from a process
+/// that is stuck rather than crashed.
+pub async fn break_lock(db: &MongoDB) -> Result<()> {
+    db.col("migrations")
+        .delete_one(doc! { "_id": LOCK_ID }, None)
+        .await
+        .map_err(|e| Error::database("delete_one", "migrations", e))?;
+
+    Ok(())
+}
+
+/// Try to take the lock once, returning the current holder if it is taken.
+async fn try_acquire(db: &MongoDB, owner: &str) -> Result<Option<String>> {
+    let migrations = db.col("migrations");
+
+    // Take over a lock we already hold or whose lease has run out.
+    let taken = migrations
+        .update_one(
+            doc! {
+                "_id": LOCK_ID,
+                "$or": [
+                    { "owner": owner },
+                    { "expires_at": { "$lt": Utc::now() } }
+                ]
+            },
+            doc! { "$set": { "owner": owner, "expires_at": expiry() } },
+            None,
+        )
+        .await
+        .map_err(|e| Error::database("update_one", "migrations", e))?;
+
+    if taken.matched_count > 0 {
+        return Ok(None);
+    }
+
+    // Otherwise create it; this only fails if someone else got there first.
+    let inserted = migrations
+        .insert_one(
+            doc! { "_id": LOCK_ID, "owner": owner, "expires_at": expiry() },
+            None,
+        )
+        .await;
+
+    match inserted {
+        Ok(_) => Ok(None),
+        Err(error) => match holder(db).await? {
+            Some(holder) => Ok(Some(holder)),
+            None => Err(Error::database("insert_one", "migrations", error)),
+        },
+    }
+}
+
+async fn holder(db: &MongoDB) -> Result<Option<String>> {
+    let lock = db
+        .col("migrations")
+        .find_one(doc! { "_id": LOCK_ID }, None)
+        .await
+        .map_err(|e| Error::database("find_one", "migrations", e))?;
+
+    Ok(lock.and_then(|doc| doc.get_str("owner").ok().map(String::from)))
+}
+
+fn expiry() -> chrono::DateTime<Utc> {
+    Utc::now() + chrono::Duration::seconds(LEASE_SECONDS)
+}
@@ -1,2 +1,5 @@
+pub mod batch;
 pub mod init;
+pub mod lock;
+pub mod revisions;
 pub mod scripts;
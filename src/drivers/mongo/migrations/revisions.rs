@@ -0,0 +1,407 @@
+use super::batch::update_in_batches;
+use crate::drivers::mongo::{document_id, MongoDB};
+use crate::permissions;
+use crate::util::result::{Error, Result};
+use mongodb::bson::{doc, to_document, Document};
+use rocket::async_trait;
+use serde::Serialize;
+
+pub type PermissionTuple = (
+    i32, // server permission
+    i32, // channel permission
+);
+
+/// A single schema or data change, applied once per database.
+#[async_trait]
+pub trait Migration: Send + Sync {
+    /// Position in the registry; also the `_id` it is recorded under.
+    fn id(&self) -> i32;
+    fn date(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+
+    async fn up(&self, db: &MongoDB) -> Result<()>;
+
+    /// Number of documents `up` would modify, without changing anything.
+    async fn estimate(&self, _db: &MongoDB) -> Result<u64> {
+        Ok(0)
+    }
+
+    /// Undo `up`. Migrations that drop or rewrite data cannot be reverted.
+    async fn down(&self, _db: &MongoDB) -> Result<()> {
+        Err(Error::IrreversibleMigration {
+            revision: self.id(),
+        })
+    }
+}
+
+/// Every migration, in the order they are applied.
+///
+/// New migrations go at the end with the next ID; never renumber or remove
+/// one that has been released.
+pub fn registry() -> Vec<Box<dyn Migration>> {
+    vec![
+        Box::new(TestMigrationSystem),
+        Box::new(AutumnV1),
+        Box::new(AddServers),
+        Box::new(MultipleAttachments),
+        Box::new(AddServerCollections),
+        Box::new(AddPermissions),
+        Box::new(AddMessageTextIndex),
+        Box::new(AddBots),
+        Box::new(AddJobs),
+    ]
+}
+
+/// ID of the last migration this build knows about.
+pub fn latest_revision() -> i32 {
+    registry()
+        .last()
+        .map(|migration| migration.id())
+        .unwrap_or(-1)
+}
+
+async fn count(db: &MongoDB, collection: &'static str, filter: Document) -> Result<u64> {
+    Ok(db
+        .col(collection)
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| Error::database("count_documents", collection, e))? as u64)
+}
+
+struct TestMigrationSystem;
+
+#[async_trait]
+impl Migration for TestMigrationSystem {
+    fn id(&self) -> i32 {
+        0
+    }
+
+    fn date(&self) -> &'static str {
+        "2021-04-24"
+    }
+
+    fn description(&self) -> &'static str {
+        "Test migration system"
+    }
+
+    async fn up(&self, _db: &MongoDB) -> Result<()> {
+        Ok(())
+    }
+
+    async fn down(&self, _db: &MongoDB) -> Result<()> {
+        Ok(())
+    }
+}
+
+struct AutumnV1;
+
+#[async_trait]
+impl Migration for AutumnV1 {
+    fn id(&self) -> i32 {
+        1
+    }
+
+    fn date(&self) -> &'static str {
+        "2021-04-24"
+    }
+
+    fn description(&self) -> &'static str {
+        "Migrate to Autumn v1.0.0"
+    }
+
+    async fn up(&self, db: &MongoDB) -> Result<()> {
+        db.col("messages")
+            .update_many(
+                doc! { "attachment": { "$exists": 1 } },
+                doc! { "$set": { "attachment.tag": "attachments", "attachment.size": 0 } },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_many", "messages", e))?;
+
+        db.col("attachments")
+            .update_many(
+                doc! {},
+                doc! { "$set": { "tag": "attachments", "size": 0 } },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_many", "attachments", e))?;
+
+        Ok(())
+    }
+
+    async fn estimate(&self, db: &MongoDB) -> Result<u64> {
+        let messages = count(db, "messages", doc! { "attachment": { "$exists": 1 } }).await?;
+        let attachments = count(db, "attachments", doc! {}).await?;
+        Ok(messages + attachments)
+    }
+}
+
+struct AddServers;
+
+#[async_trait]
+impl Migration for AddServers {
+    fn id(&self) -> i32 {
+        2
+    }
+
+    fn date(&self) -> &'static str {
+        "2021-05-08"
+    }
+
+    fn description(&self) -> &'static str {
+        "Add servers collection"
+    }
+
+    async fn up(&self, db: &MongoDB) -> Result<()> {
+        db.revolt
+            .create_collection(&db.collection_name("servers"), None)
+            .await
+            .map_err(|e| Error::database("create_collection", "servers", e))
+    }
+}
+
+struct MultipleAttachments;
+
+#[async_trait]
+impl Migration for MultipleAttachments {
+    fn id(&self) -> i32 {
+        3
+    }
+
+    fn date(&self) -> &'static str {
+        "2021-05-25"
+    }
+
+    fn description(&self) -> &'static str {
+        "Support multiple file uploads, add channel_unreads and user_settings"
+    }
+
+    async fn up(&self, db: &MongoDB) -> Result<()> {
+        update_in_batches(
+            db,
+            self.id(),
+            "messages",
+            doc! {
+                "attachment": {
+                    "$exists": 1
+                }
+            },
+            doc! {
+                "_id": 1,
+                "attachments": [ "$attachment" ]
+            },
+            |doc| {
+                let attachments = doc.get_array("attachments").map_err(|e| {
+                    Error::InvalidDocument {
+                        collection: "messages",
+                        id: document_id(doc),
+                        source: Some(Box::new(e)),
+                    }
+                })?;
+
+                Ok(doc! { "$unset": { "attachment": 1 }, "$set": { "attachments": attachments.clone() } })
+            },
+        )
+        .await?;
+
+        db.revolt
+            .create_collection(&db.collection_name("channel_unreads"), None)
+            .await
+            .map_err(|e| Error::database("create_collection", "channel_unreads", e))?;
+
+        db.revolt
+            .create_collection(&db.collection_name("user_settings"), None)
+            .await
+            .map_err(|e| Error::database("create_collection", "user_settings", e))
+    }
+
+    async fn estimate(&self, db: &MongoDB) -> Result<u64> {
+        count(db, "messages", doc! { "attachment": { "$exists": 1 } }).await
+    }
+}
+
+struct AddServerCollections;
+
+#[async_trait]
+impl Migration for AddServerCollections {
+    fn id(&self) -> i32 {
+        4
+    }
+
+    fn date(&self) -> &'static str {
+        "2021-06-01"
+    }
+
+    fn description(&self) -> &'static str {
+        "Add more server collections"
+    }
+
+    async fn up(&self, db: &MongoDB) -> Result<()> {
+        db.revolt
+            .create_collection(&db.collection_name("server_members"), None)
+            .await
+            .map_err(|e| Error::database("create_collection", "server_members", e))?;
+
+        db.revolt
+            .create_collection(&db.collection_name("server_bans"), None)
+            .await
+            .map_err(|e| Error::database("create_collection", "server_bans", e))?;
+
+        db.revolt
+            .create_collection(&db.collection_name("channel_invites"), None)
+            .await
+            .map_err(|e| Error::database("create_collection", "channel_invites", e))
+    }
+}
+
+struct AddPermissions;
+
+#[async_trait]
+impl Migration for AddPermissions {
+    fn id(&self) -> i32 {
+        5
+    }
+
+    fn date(&self) -> &'static str {
+        "2021-06-26"
+    }
+
+    fn description(&self) -> &'static str {
+        "Add permissions"
+    }
+
+    async fn up(&self, db: &MongoDB) -> Result<()> {
+        #[derive(Serialize)]
+        struct Server {
+            pub default_permissions: PermissionTuple,
+        }
+
+        let server = Server {
+            default_permissions: (
+                *permissions::server::DEFAULT_PERMISSION as i32,
+                *permissions::channel::DEFAULT_PERMISSION_SERVER as i32,
+            ),
+        };
+
+        db.col("servers")
+            .update_many(
+                doc! {},
+                doc! {
+                    "$set": to_document(&server).map_err(|e| Error::database("to_document", "servers", e))?
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_many", "servers", e))?;
+
+        Ok(())
+    }
+
+    async fn estimate(&self, db: &MongoDB) -> Result<u64> {
+        count(db, "servers", doc! {}).await
+    }
+}
+
+struct AddMessageTextIndex;
+
+#[async_trait]
+impl Migration for AddMessageTextIndex {
+    fn id(&self) -> i32 {
+        6
+    }
+
+    fn date(&self) -> &'static str {
+        "2021-07-09"
+    }
+
+    fn description(&self) -> &'static str {
+        "Add message text index"
+    }
+
+    async fn up(&self, db: &MongoDB) -> Result<()> {
+        db.revolt
+            .run_command(
+                doc! {
+                    "createIndexes": db.collection_name("messages"),
+                    "indexes": [
+                        {
+                            "key": {
+                                "content": "text"
+                            },
+                            "name": "content"
+                        }
+                    ]
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("create_indexes", "messages", e))?;
+
+        Ok(())
+    }
+
+    async fn down(&self, db: &MongoDB) -> Result<()> {
+        db.revolt
+            .run_command(
+                doc! {
+                    "dropIndexes": db.collection_name("messages"),
+                    "index": "content"
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("drop_indexes", "messages", e))?;
+
+        Ok(())
+    }
+}
+
+struct AddBots;
+
+#[async_trait]
+impl Migration for AddBots {
+    fn id(&self) -> i32 {
+        7
+    }
+
+    fn date(&self) -> &'static str {
+        "2021-08-11"
+    }
+
+    fn description(&self) -> &'static str {
+        "Add bots collection"
+    }
+
+    async fn up(&self, db: &MongoDB) -> Result<()> {
+        db.revolt
+            .create_collection(&db.collection_name("bots"), None)
+            .await
+            .map_err(|e| Error::database("create_collection", "bots", e))
+    }
+}
+
+struct AddJobs;
+
+#[async_trait]
+impl Migration for AddJobs {
+    fn id(&self) -> i32 {
+        8
+    }
+
+    fn date(&self) -> &'static str {
+        "2026-10-18"
+    }
+
+    fn description(&self) -> &'static str {
+        "Add jobs collection"
+    }
+
+    async fn up(&self, db: &MongoDB) -> Result<()> {
+        db.revolt
+            .create_collection(&db.collection_name("jobs"), None)
+            .await
+            .map_err(|e| Error::database("create_collection", "jobs", e))
+    }
+}
@@ -1,217 +1,244 @@
-use crate::permissions;
+use super::revisions::{latest_revision, registry, Migration};
+use crate::drivers::mongo::{decode, MongoDB};
+use crate::util::result::{Error, Result};
+use chrono::Utc;
 use futures::StreamExt;
 use log::info;
 use mongodb::{
-    bson::{doc, from_document, to_document},
-    options::FindOptions,
-    Database,
+    bson::{doc, DateTime},
+    options::{FindOptions, ReplaceOptions},
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
-pub type PermissionTuple = (
-    i32, // server permission
-    i32, // channel permission
-);
-
-#[derive(Serialize, Deserialize)]
-struct MigrationInfo {
+/// Single document used before migrations were recorded individually,
+/// holding the next revision to run.
+#[derive(Deserialize)]
+struct LegacyMigrationInfo {
     _id: i32,
     revision: i32,
 }
 
-pub const LATEST_REVISION: i32 = 8;
-
-pub async fn migrate_database(db: &Database) {
-    let migrations = db.collection("migrations");
-    let data = migrations
-        .find_one(None, None)
-        .await
-        .expect("Failed to fetch migration data.");
-
-    if let Some(doc) = data {
-        let info: MigrationInfo =
-            from_document(doc).expect("Failed to read migration information.");
-
-        let revision = run_migrations(db, info.revision).await;
-
-        migrations
-            .update_one(
-                doc! {
-                    "_id": info._id
-                },
-                doc! {
-                    "$set": {
-                        "revision": revision
-                    }
-                },
-                None,
-            )
-            .await
-            .expect("Failed to commit migration information.");
+/// Record of one migration having been applied to this database.
+#[derive(Deserialize, Debug, Clone)]
+pub struct AppliedMigration {
+    #[serde(rename = "_id")]
+    pub id: i32,
+    pub date: String,
+    pub description: String,
+    pub applied_at: DateTime,
+}
 
-        info!("Migration complete. Currently at revision {}.", revision);
-    } else {
-        panic!("Database was configured incorrectly, possibly because initalization failed.")
-    }
+/// A migration that has not been applied yet.
+#[derive(Serialize, Debug, Clone)]
+pub struct PendingMigration {
+    pub id: i32,
+    pub date: &'static str,
+    pub description: &'static str,
+    /// Documents the migration would modify; only filled in by a dry run.
+    pub documents: Option<u64>,
 }
 
-pub async fn run_migrations(db: &Database, revision: i32) -> i32 {
+pub async fn migrate_database(db: &MongoDB) -> Result<()> {
     info!("Starting database migration.");
 
-    if revision <= 0 {
-        info!("Running migration [revision 0]: Test migration system.");
-    }
+    let applied = applied_ids(db).await?;
+    convert_legacy_info(db).await?;
 
-    if revision <= 1 {
-        info!("Running migration [revision 1 / 2021-04-24]: Migrate to Autumn v1.0.0.");
+    for migration in registry() {
+        if applied.contains(&migration.id()) {
+            continue;
+        }
 
-        let messages = db.collection("messages");
-        let attachments = db.collection("attachments");
+        info!(
+            "Running migration [revision {} / {}]: {}.",
+            migration.id(),
+            migration.date(),
+            migration.description()
+        );
 
-        messages
-            .update_many(
-                doc! { "attachment": { "$exists": 1 } },
-                doc! { "$set": { "attachment.tag": "attachments", "attachment.size": 0 } },
-                None,
-            )
-            .await
-            .expect("Failed to update messages.");
-
-        attachments
-            .update_many(
-                doc! {},
-                doc! { "$set": { "tag": "attachments", "size": 0 } },
-                None,
-            )
-            .await
-            .expect("Failed to update attachments.");
+        migration.up(db).await?;
+        record(db, migration.as_ref()).await?;
     }
 
-    if revision <= 2 {
-        info!("Running migration [revision 2 / 2021-05-08]: Add servers collection.");
+    info!(
+        "Migration complete. Currently at revision {}.",
+        latest_revision()
+    );
+    Ok(())
+}
 
-        db.create_collection("servers", None)
-            .await
-            .expect("Failed to create servers collection.");
-    }
+/// Run the `down` step of every applied migration after `target`, newest
+/// first, stopping at the first one that cannot be reverted.
+pub async fn revert_migrations(db: &MongoDB, target: i32) -> Result<()> {
+    let applied = applied_ids(db).await?;
+    convert_legacy_info(db).await?;
 
-    if revision <= 3 {
-        info!("Running migration [revision 3 / 2021-05-25]: Support multiple file uploads, add channel_unreads and user_settings.");
-
-        let messages = db.collection("messages");
-        let mut cursor = messages
-            .find(
-                doc! {
-                    "attachment": {
-                        "$exists": 1
-                    }
-                },
-                FindOptions::builder()
-                    .projection(doc! {
-                        "_id": 1,
-                        "attachments": [ "$attachment" ]
-                    })
-                    .build(),
-            )
-            .await
-            .expect("Failed to fetch messages.");
-
-        while let Some(result) = cursor.next().await {
-            let doc = result.unwrap();
-            let id = doc.get_str("_id").unwrap();
-            let attachments = doc.get_array("attachments").unwrap();
-
-            messages
-                .update_one(
-                    doc! { "_id": id },
-                    doc! { "$unset": { "attachment": 1 }, "$set": { "attachments": attachments } },
-                    None,
-                )
-                .await
-                .unwrap();
+    for migration in registry().iter().rev() {
+        if migration.id() <= target || !applied.contains(&migration.id()) {
+            continue;
         }
 
-        db.create_collection("channel_unreads", None)
-            .await
-            .expect("Failed to create channel_unreads collection.");
+        info!(
+            "Reverting migration [revision {} / {}]: {}.",
+            migration.id(),
+            migration.date(),
+            migration.description()
+        );
 
-        db.create_collection("user_settings", None)
+        migration.down(db).await?;
+        db.col("migrations")
+            .delete_one(doc! { "_id": migration.id() }, None)
             .await
-            .expect("Failed to create user_settings collection.");
+            .map_err(|e| Error::database("delete_one", "migrations", e))?;
     }
 
-    if revision <= 4 {
-        info!("Running migration [revision 4 / 2021-06-01]: Add more server collections.");
-
-        db.create_collection("server_members", None)
-            .await
-            .expect("Failed to create server_members collection.");
+    Ok(())
+}
 
-        db.create_collection("server_bans", None)
-            .await
-            .expect("Failed to create server_bans collection.");
+/// Migrations that `migrate_database` would run, in order, without
+/// changing anything.
+pub async fn plan(db: &MongoDB) -> Result<Vec<PendingMigration>> {
+    let applied = applied_ids(db).await?;
+
+    Ok(registry()
+        .into_iter()
+        .filter(|migration| !applied.contains(&migration.id()))
+        .map(|migration| PendingMigration {
+            id: migration.id(),
+            date: migration.date(),
+            description: migration.description(),
+            documents: None,
+        })
+        .collect())
+}
 
-        db.create_collection("channel_invites", None)
-            .await
-            .expect("Failed to create channel_invites collection.");
+/// Like `plan`, also counting the documents each migration would modify.
+///
+/// Counts are taken against the current data, so a migration that depends
+/// on an earlier pending one may end up touching more documents.
+pub async fn dry_run(db: &MongoDB) -> Result<Vec<PendingMigration>> {
+    let mut pending = plan(db).await?;
+    let registry = registry();
+
+    for entry in &mut pending {
+        if let Some(migration) = registry.iter().find(|m| m.id() == entry.id) {
+            entry.documents = Some(migration.estimate(db).await?);
+        }
     }
 
-    if revision <= 5 {
-        info!("Running migration [revision 5 / 2021-06-26]: Add permissions.");
-
-        #[derive(Serialize)]
-        struct Server {
-            pub default_permissions: PermissionTuple,
-        }
+    Ok(pending)
+}
 
-        let server = Server {
-            default_permissions: (
-                *permissions::server::DEFAULT_PERMISSION as i32,
-                *permissions::channel::DEFAULT_PERMISSION_SERVER as i32,
-            ),
-        };
-
-        db.collection("servers")
-            .update_many(
-                doc! {},
-                doc! {
-                    "$set": to_document(&server).unwrap()
-                },
-                None,
-            )
-            .await
-            .expect("Failed to migrate servers.");
+/// Every migration recorded as applied, ordered by ID.
+pub async fn applied_migrations(db: &MongoDB) -> Result<Vec<AppliedMigration>> {
+    // The collection may also hold bookkeeping documents, which never have
+    // a numeric ID.
+    let mut cursor = db
+        .col("migrations")
+        .find(
+            doc! { "_id": { "$type": "number" } },
+            FindOptions::builder().sort(doc! { "_id": 1 }).build(),
+        )
+        .await
+        .map_err(|e| Error::database("find", "migrations", e))?;
+
+    // Skipping an unreadable record would re-run its migration, so these
+    // are always read strictly.
+    let mut migrations = vec![];
+    while let Some(result) = cursor.next().await {
+        let doc = result.map_err(|e| Error::database("next", "migrations", e))?;
+        migrations.push(decode("migrations", doc)?);
     }
 
-    if revision <= 6 {
-        info!("Running migration [revision 6 / 2021-07-09]: Add message text index.");
+    Ok(migrations)
+}
 
-        db.run_command(
+/// Mark a migration as applied.
+pub async fn record(db: &MongoDB, migration: &dyn Migration) -> Result<()> {
+    db.col("migrations")
+        .replace_one(
+            doc! { "_id": migration.id() },
             doc! {
-                "createIndexes": "messages",
-                "indexes": [
-                    {
-                        "key": {
-                            "content": "text"
-                        },
-                        "name": "content"
-                    }
-                ]
+                "_id": migration.id(),
+                "date": migration.date(),
+                "description": migration.description(),
+                "applied_at": Utc::now()
             },
-            None,
+            ReplaceOptions::builder().upsert(true).build(),
         )
         .await
-        .expect("Failed to create message index.");
+        .map_err(|e| Error::database("replace_one", "migrations", e))?;
+
+    Ok(())
+}
+
+/// IDs of applied migrations, refusing to continue if any of them are
+/// unknown to this build.
+async fn applied_ids(db: &MongoDB) -> Result<HashSet<i32>> {
+    let known: HashSet<i32> = registry().iter().map(|migration| migration.id()).collect();
+    let applied: HashSet<i32> = match legacy_info(db).await? {
+        Some(info) => (0..info.revision).collect(),
+        None => applied_migrations(db)
+            .await?
+            .into_iter()
+            .map(|migration| migration.id)
+            .collect(),
+    };
+
+    if let Some(revision) = applied.difference(&known).max() {
+        return Err(Error::DatabaseTooNew {
+            revision: *revision,
+            supported: latest_revision(),
+        });
     }
 
-    if revision <= 7 {
-        info!("Running migration [revision 7 / 2021-08-11]: Add message text index.");
+    Ok(applied)
+}
 
-        db.create_collection("bots", None)
-            .await
-            .expect("Failed to create bots collection.");
+/// The legacy `{ _id: 0, revision }` document, if not yet converted.
+async fn legacy_info(db: &MongoDB) -> Result<Option<LegacyMigrationInfo>> {
+    let legacy = db
+        .col("migrations")
+        .find_one(doc! { "revision": { "$exists": true } }, None)
+        .await
+        .map_err(|e| Error::database("find_one", "migrations", e))?;
+
+    legacy.map(|doc| decode("migrations", doc)).transpose()
+}
+
+/// Replace the legacy document with one record per migration it covered.
+///
+/// Callers check the revision is supported with `applied_ids` first.
+async fn convert_legacy_info(db: &MongoDB) -> Result<()> {
+    let info = match legacy_info(db).await? {
+        Some(info) => info,
+        None => return Ok(()),
+    };
+
+    info!(
+        "Converting legacy migration info at revision {}.",
+        info.revision
+    );
+
+    // Revision 0 shares its ID with the legacy document, so write it last:
+    // if we stop part way through, the legacy document is still there and
+    // the conversion is simply repeated.
+    let registry = registry();
+    for migration in registry.iter().rev() {
+        if migration.id() < info.revision {
+            record(db, migration.as_ref()).await?;
+        }
     }
 
-    // Reminder to update LATEST_REVISION when adding new migrations.
-    LATEST_REVISION
+    // Only still present if no migration replaced it.
+    db.col("migrations")
+        .delete_one(
+            doc! { "_id": info._id, "revision": { "$exists": true } },
+            None,
+        )
+        .await
+        .map_err(|e| Error::database("delete_one", "migrations", e))?;
+
+    Ok(())
 }
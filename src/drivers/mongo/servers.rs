@@ -0,0 +1,256 @@
+use super::{decode, MongoDB};
+use crate::entities::Server;
+use crate::repositories::{
+    AttachmentRepository, ChannelRepository, InviteRepository, MessageRepository, ServerRepository,
+    UnreadRepository,
+};
+use crate::util::result::{Error, Result};
+use mongodb::{
+    bson::{doc, to_bson, to_document, Document},
+    options::FindOptions,
+};
+use rocket::async_trait;
+
+#[async_trait]
+impl ServerRepository for MongoDB {
+    async fn get_server_by_id(&self, id: &str) -> Result<Server> {
+        let doc = self
+            .col("servers")
+            .find_one(doc! { "_id": id }, None)
+            .await
+            .map_err(|e| Error::database("find_one", "servers", e).for_id(id))?
+            .ok_or_else(|| Error::UnknownServer)?;
+
+        decode::<Server>("servers", doc)
+    }
+
+    async fn update_server_permissions(
+        &self,
+        server_id: &str,
+        role_id: &str,
+        server_permissions: i32,
+        channel_permissions: i32,
+    ) -> Result<()> {
+        self.col("servers")
+            .update_one(
+                doc! { "_id": server_id },
+                doc! {
+                    "$set": {
+                        "roles.".to_owned() + role_id + &".permissions": [
+                            server_permissions,
+                            channel_permissions
+                        ]
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "servers", e))?;
+        Ok(())
+    }
+
+    async fn update_server_default_permissions(
+        &self,
+        server_id: &str,
+        server_permissions: i32,
+        channel_permissions: i32,
+    ) -> Result<()> {
+        self.col("servers")
+            .update_one(
+                doc! { "_id": server_id },
+                doc! {
+                    "$set": {
+                        "default_permissions": [
+                            server_permissions,
+                            channel_permissions
+                        ]
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "servers", e))?;
+        Ok(())
+    }
+
+    async fn apply_server_changes(&self, server_id: &str, change_doc: Document) -> Result<()> {
+        self.col("servers")
+            .update_one(
+                doc! {
+                    "_id": server_id
+                },
+                change_doc,
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "servers", e))?;
+        Ok(())
+    }
+
+    async fn add_server(&self, server: &Server) -> Result<()> {
+        self.col("servers")
+            .insert_one(
+                to_document(server).map_err(|e| Error::database("to_bson", "servers", e))?,
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("insert_one", "servers", e))?;
+
+        Ok(())
+    }
+
+    async fn get_servers(&self, server_ids: Vec<&str>) -> Result<Vec<Server>> {
+        let cursor = self
+            .col("servers")
+            .find(
+                doc! {
+                    "_id": {
+                        "$in": server_ids
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find", "servers", e))?;
+
+        self.collect::<Server, _>("servers", cursor).await
+    }
+
+    async fn add_channel_to_server(&self, server_id: &str, channel_id: &str) -> Result<()> {
+        self.col("servers")
+            .update_one(
+                doc! {
+                    "_id": server_id
+                },
+                doc! {
+                    "$addToSet": {
+                        "channels": channel_id
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "servers", e))?;
+        Ok(())
+    }
+
+    async fn create_role(
+        &self,
+        server_id: &str,
+        role_id: &str,
+        role_name: &str,
+        default_permission: i32,
+        default_permission_server: i32,
+    ) -> Result<()> {
+        self.col("servers")
+            .update_one(
+                doc! {
+                    "_id": server_id
+                },
+                doc! {
+                    "$set": {
+                        "roles.".to_owned() + role_id: {
+                            "name": role_name,
+                            "permissions": [
+                                default_permission,
+                                default_permission_server
+                            ]
+                        }
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "servers", e))?;
+        Ok(())
+    }
+
+    async fn delete_role(&self, server_id: &str, role_id: &str) -> Result<()> {
+        self.col("servers")
+            .update_one(
+                doc! {
+                    "_id": server_id
+                },
+                doc! {
+                    "$unset": {
+                        "roles.".to_owned() + role_id: 1
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "servers", e))?;
+        Ok(())
+    }
+
+    async fn does_server_exist_by_nonce(&self, nonce: &str) -> Result<bool> {
+        Ok(self
+            .col("servers")
+            .find_one(
+                doc! {
+                    "nonce": nonce
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find_one", "servers", e))?
+            .is_some())
+    }
+
+    async fn delete_server(&self, id: &str) -> Result<()> {
+        let server = self.get_server_by_id(id).await?;
+
+        // Channels point at their server, so this also finds channels the
+        // server's list lost track of.
+        let cursor = self
+            .col("channels")
+            .find(
+                doc! { "server": id },
+                FindOptions::builder().projection(doc! { "_id": 1 }).build(),
+            )
+            .await
+            .map_err(|e| Error::database("find", "channels", e).for_id(id))?;
+        let channel_ids = self.collect_ids("channels", cursor).await?;
+
+        for channel_id in &channel_ids {
+            let message_ids = self
+                .get_ids_from_messages_with_attachments(channel_id)
+                .await?;
+            self.delete_attachments_of_messages(message_ids.iter().map(|x| x.as_str()).collect())
+                .await?;
+            self.delete_messages_from_channel(channel_id).await?;
+            self.delete_invites_associated_to_channel(channel_id)
+                .await?;
+            self.delete_channel_unreads(channel_id).await?;
+        }
+
+        self.delete_all_channels_from_server(id).await?;
+
+        let files: Vec<&str> = server
+            .icon
+            .iter()
+            .chain(server.banner.iter())
+            .map(|file| file.id.as_str())
+            .collect();
+        if !files.is_empty() {
+            self.delete_attachments(files).await?;
+        }
+
+        self.col("server_members")
+            .delete_many(doc! { "_id.server": id }, None)
+            .await
+            .map_err(|e| Error::database("delete_many", "server_members", e).for_id(id))?;
+
+        self.col("server_bans")
+            .delete_many(doc! { "_id.server": id }, None)
+            .await
+            .map_err(|e| Error::database("delete_many", "server_bans", e).for_id(id))?;
+
+        self.col("servers")
+            .delete_one(doc! { "_id": id }, None)
+            .await
+            .map_err(|e| Error::database("delete_one", "servers", e).for_id(id))?;
+
+        Ok(())
+    }
+}
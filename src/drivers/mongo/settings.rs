@@ -0,0 +1,122 @@
+use super::{is_duplicate_key, MongoDB};
+use crate::entities::{UserSettingsEntry, UserSettingsSyncResult};
+use crate::repositories::SettingsRepository;
+use crate::util::result::{Error, Result};
+use mongodb::{
+    bson::{doc, Document},
+    options::{FindOneOptions, UpdateOptions},
+};
+use rocket::async_trait;
+
+impl MongoDB {
+    /// Make sure a user has a settings document, so that entries only ever
+    /// need a conditional update.
+    async fn create_user_settings(&self, user_id: &str) -> Result<()> {
+        match self
+            .col("user_settings")
+            .insert_one(doc! { "_id": user_id }, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) if is_duplicate_key(&e) => Ok(()),
+            Err(e) => Err(Error::database("insert_one", "user_settings", e).for_id(user_id)),
+        }
+    }
+
+    /// Stores a single settings entry unless the stored value is at least as new.
+    async fn apply_user_setting(&self, user_id: &str, entry: &UserSettingsEntry) -> Result<bool> {
+        Ok(self
+            .col("user_settings")
+            .update_one(
+                doc! {
+                    "_id": user_id,
+                    "$or": [
+                        { entry.key.clone(): { "$exists": false } },
+                        { format!("{}.0", entry.key): { "$lt": entry.timestamp } }
+                    ]
+                },
+                doc! {
+                    "$set": {
+                        entry.key.clone(): [ entry.timestamp, entry.value.clone() ]
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "user_settings", e).for_id(user_id))?
+            .matched_count
+            > 0)
+    }
+}
+
+#[async_trait]
+impl SettingsRepository for MongoDB {
+    async fn update_user_settings(&self, user_id: &str, set_doc: Document) -> Result<()> {
+        self.col("user_settings")
+            .update_one(
+                doc! {
+                    "_id": user_id
+                },
+                doc! {
+                    "$set": set_doc
+                },
+                UpdateOptions::builder().upsert(true).build(),
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "user_settings", e))?;
+        Ok(())
+    }
+
+    async fn get_user_settings_doc(
+        &self,
+        user_id: &str,
+        option_keys: Vec<&str>,
+    ) -> Result<Option<Document>> {
+        let mut projection = doc! {
+            "_id": 0,
+        };
+        for key in option_keys {
+            projection.insert(key, 1);
+        }
+        Ok(self
+            .col("user_settings")
+            .find_one(
+                doc! {
+                    "_id": user_id
+                },
+                FindOneOptions::builder().projection(projection).build(),
+            )
+            .await
+            .map_err(|e| Error::database("find_one", "user_settings", e))?)
+    }
+
+    async fn sync_user_settings(
+        &self,
+        user_id: &str,
+        entries: Vec<UserSettingsEntry>,
+    ) -> Result<UserSettingsSyncResult> {
+        let mut result = UserSettingsSyncResult::default();
+        if !entries.is_empty() {
+            self.create_user_settings(user_id).await?;
+        }
+
+        for entry in entries {
+            // Keys become field names, they must not be able to address anything else.
+            if entry.key.is_empty()
+                || entry.key == "_id"
+                || entry.key.starts_with('$')
+                || entry.key.contains('.')
+            {
+                result.rejected.push(entry.key);
+                continue;
+            }
+
+            if self.apply_user_setting(user_id, &entry).await? {
+                result.accepted.push(entry.key);
+            } else {
+                result.rejected.push(entry.key);
+            }
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,311 @@
+use super::{check_write_errors, MongoDB};
+use crate::entities::{ChannelUnread, ChannelUnreadCount};
+use crate::repositories::{ServerRepository, UnreadRepository};
+use crate::util::result::{Error, Result};
+use futures::StreamExt;
+use mongodb::{
+    bson::{doc, Bson, Document},
+    options::UpdateOptions,
+};
+use rocket::async_trait;
+use ulid::Ulid;
+
+#[async_trait]
+impl UnreadRepository for MongoDB {
+    async fn delete_channel_unreads(&self, channel_id: &str) -> Result<()> {
+        self.col("channel_unreads")
+            .delete_many(
+                doc! {
+                    "_id.channel": channel_id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("delete_many", "channel_unreads", e))?;
+        Ok(())
+    }
+
+    async fn delete_unreads_for_user(&self, user_id: &str) -> Result<()> {
+        self.col("channel_unreads")
+            .delete_many(
+                doc! {
+                    "_id.user": user_id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("delete_many", "channel_unreads", e).for_id(user_id))?;
+        Ok(())
+    }
+
+    async fn delete_multi_channel_unreads_for_user(
+        &self,
+        channel_ids: Vec<&str>,
+        user_id: &str,
+    ) -> Result<()> {
+        self.col("channel_unreads")
+            .delete_many(
+                doc! {
+                    "_id.channel": {
+                        "$in": channel_ids
+                    },
+                    "_id.user": user_id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("delete_many", "channel_unreads", e))?;
+        Ok(())
+    }
+
+    async fn add_mentions_to_channel_unreads(
+        &self,
+        channel_id: &str,
+        mentions: Vec<&str>,
+        message: &str,
+    ) -> Result<()> {
+        self.col("channel_unreads")
+            .update_many(
+                doc! {
+                    "_id.channel": channel_id,
+                    "_id.user": {
+                        "$in": mentions
+                    }
+                },
+                doc! {
+                    "$push": {
+                        "mentions": message
+                    }
+                },
+                UpdateOptions::builder().upsert(true).build(),
+            )
+            .await
+            .map_err(|e| Error::database("update_many", "channel_unreads", e))?;
+        Ok(())
+    }
+
+    async fn add_channels_to_unreads_for_user(
+        &self,
+        channel_ids: Vec<&str>,
+        user_id: &str,
+        current_time: &str,
+    ) -> Result<()> {
+        self.col("channel_unreads")
+            .insert_many(
+                channel_ids
+                    .iter()
+                    .map(|channel| {
+                        doc! {
+                            "_id": {
+                                "channel": channel,
+                                "user": user_id
+                            },
+                            "last_id": current_time
+                        }
+                    })
+                    .collect::<Vec<Document>>(),
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_many", "channel_unreads", e))
+            .map(|_| ())
+    }
+
+    async fn get_unreads_for_user(&self, user_id: &str) -> Result<Vec<Document>> {
+        let cursor = self
+            .col("channel_unreads")
+            .find(
+                doc! {
+                    "_id.user": user_id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find_one", "channel_unreads", e))?;
+
+        self.collect::<Document, _>("channel_unreads", cursor).await
+    }
+
+    async fn update_last_message_in_channel_unreads(
+        &self,
+        channel_id: &str,
+        user_id: &str,
+        message_id: &str,
+    ) -> Result<()> {
+        self.col("channel_unreads")
+            .update_one(
+                doc! {
+                    "_id.channel": channel_id,
+                    "_id.user": user_id
+                },
+                doc! {
+                    "$unset": {
+                        "mentions": 1
+                    },
+                    "$set": {
+                        "last_id": message_id
+                    }
+                },
+                UpdateOptions::builder().upsert(true).build(),
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "channel_unreads", e))?;
+        Ok(())
+    }
+
+    async fn get_unread_counts_for_user(
+        &self,
+        user_id: &str,
+        channel_ids: Vec<&str>,
+    ) -> Result<Vec<ChannelUnreadCount>> {
+        let cursor = self
+            .col("channel_unreads")
+            .find(
+                doc! {
+                    "_id.user": user_id,
+                    "_id.channel": {
+                        "$in": &channel_ids
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find", "channel_unreads", e))?;
+
+        let unreads = self
+            .collect::<ChannelUnread, _>("channel_unreads", cursor)
+            .await?;
+
+        // Without an acknowledgement every message in the channel is unread.
+        let channels: Vec<Document> = channel_ids
+            .iter()
+            .map(|channel_id| {
+                let mut filter = doc! { "channel": *channel_id };
+                if let Some(last_id) = unreads
+                    .iter()
+                    .find(|x| x.id.channel == *channel_id)
+                    .and_then(|x| x.last_id.as_ref())
+                {
+                    filter.insert("_id", doc! { "$gt": last_id });
+                }
+
+                filter
+            })
+            .collect();
+
+        let mut totals = vec![];
+        if !channels.is_empty() {
+            let mut cursor = self
+                .col("messages")
+                .aggregate(
+                    vec![
+                        doc! {
+                            "$match": {
+                                "$or": channels,
+                                "author": {
+                                    "$ne": user_id
+                                },
+                                "deleted": {
+                                    "$exists": false
+                                }
+                            }
+                        },
+                        doc! {
+                            "$group": {
+                                "_id": "$channel",
+                                "count": { "$sum": 1 }
+                            }
+                        },
+                    ],
+                    None,
+                )
+                .await
+                .map_err(|e| Error::database("aggregate", "messages", e))?;
+
+            while let Some(result) = cursor.next().await {
+                let total = result.map_err(|e| Error::database("next", "messages", e))?;
+                let count = match total.get("count") {
+                    Some(Bson::Int32(count)) => *count as u64,
+                    Some(Bson::Int64(count)) => *count as u64,
+                    _ => 0,
+                };
+
+                if let Ok(channel) = total.get_str("_id") {
+                    totals.push((channel.to_string(), count));
+                }
+            }
+        }
+
+        Ok(channel_ids
+            .into_iter()
+            .map(|channel_id| ChannelUnreadCount {
+                channel: channel_id.to_string(),
+                unread: totals
+                    .iter()
+                    .find(|(channel, _)| channel == channel_id)
+                    .map(|(_, count)| *count)
+                    .unwrap_or_default(),
+                mentions: unreads
+                    .iter()
+                    .find(|x| x.id.channel == channel_id)
+                    .and_then(|x| x.mentions.as_ref())
+                    .map(|x| x.len() as u64)
+                    .unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    async fn mark_server_as_read(&self, server_id: &str, user_id: &str) -> Result<()> {
+        let server = self
+            .get_servers(vec![server_id])
+            .await?
+            .pop()
+            .ok_or_else(|| Error::UnknownServer)?;
+
+        if server.channels.is_empty() {
+            return Ok(());
+        }
+
+        // One upsert per channel in a single command, rather than deleting
+        // and reinserting, so the user's read state never disappears and a
+        // concurrent mention is not lost.
+        let current_time = Ulid::new().to_string();
+        let ids: Vec<Bson> = server
+            .channels
+            .iter()
+            .map(|channel| Bson::Document(doc! { "channel": channel, "user": user_id }))
+            .collect();
+        let updates: Vec<Document> = ids
+            .iter()
+            .map(|id| {
+                doc! {
+                    "q": { "_id": id.clone() },
+                    "u": {
+                        "$set": {
+                            "last_id": &current_time
+                        },
+                        "$unset": {
+                            "mentions": 1
+                        }
+                    },
+                    "upsert": true
+                }
+            })
+            .collect();
+
+        let response = self
+            .revolt
+            .run_command(
+                doc! {
+                    "update": self.collection_name("channel_unreads"),
+                    "updates": updates,
+                    "ordered": false
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update", "channel_unreads", e))?;
+
+        check_write_errors(&response, "update", "channel_unreads", &ids)
+    }
+}
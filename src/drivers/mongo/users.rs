@@ -0,0 +1,659 @@
+use super::{decode, document_id, MongoDB};
+use crate::entities::{BannedUser, RelationshipStatus, User};
+use crate::repositories::UserRepository;
+use crate::util::result::{Error, Result};
+use mongodb::{
+    bson::{doc, to_bson, Document},
+    options::{Collation, FindOneOptions, FindOptions},
+};
+use rocket::async_trait;
+
+impl MongoDB {
+    /// Relationship `user_id` has with `target_id`.
+    async fn get_relationship(&self, user_id: &str, target_id: &str) -> Result<RelationshipStatus> {
+        Ok(self
+            .get_user_by_id(user_id)
+            .await?
+            .relations
+            .and_then(|relations| relations.into_iter().find(|x| x.id == target_id))
+            .map(|x| x.status)
+            .unwrap_or(RelationshipStatus::None))
+    }
+
+    /// Changes the relationship `user_id` has with `target_id` from `from` to `to`,
+    /// provided it still is `from`. Returns whether the user document matched.
+    async fn set_relationship(
+        &self,
+        user_id: &str,
+        target_id: &str,
+        from: &RelationshipStatus,
+        to: &RelationshipStatus,
+    ) -> Result<bool> {
+        let status = |status: &RelationshipStatus| {
+            to_bson(status).map_err(|e| Error::database("to_bson", "users", e))
+        };
+
+        let (filter, update) = match (from, to) {
+            (RelationshipStatus::None, RelationshipStatus::None) => return Ok(true),
+            (RelationshipStatus::None, to) => (
+                doc! {
+                    "_id": user_id,
+                    "relations._id": {
+                        "$ne": target_id
+                    }
+                },
+                doc! {
+                    "$push": {
+                        "relations": {
+                            "_id": target_id,
+                            "status": status(to)?
+                        }
+                    }
+                },
+            ),
+            (from, RelationshipStatus::None) => (
+                doc! {
+                    "_id": user_id,
+                    "relations": {
+                        "$elemMatch": {
+                            "_id": target_id,
+                            "status": status(from)?
+                        }
+                    }
+                },
+                doc! {
+                    "$pull": {
+                        "relations": {
+                            "_id": target_id
+                        }
+                    }
+                },
+            ),
+            (from, to) => (
+                doc! {
+                    "_id": user_id,
+                    "relations": {
+                        "$elemMatch": {
+                            "_id": target_id,
+                            "status": status(from)?
+                        }
+                    }
+                },
+                doc! {
+                    "$set": {
+                        "relations.$.status": status(to)?
+                    }
+                },
+            ),
+        };
+
+        Ok(self
+            .col("users")
+            .update_one(filter, update, None)
+            .await
+            .map_err(|e| Error::database("update_one", "users", e))?
+            .matched_count
+            > 0)
+    }
+
+    /// Applies both sides of a relationship change and returns the new status of `user_id`.
+    /// The driver has no multi-document transactions, so the change is not atomic: every
+    /// side is guarded by the state it is expected to be in, and if the second side fails
+    /// the first is reverted on a best-effort basis. A failed revert leaves the two sides
+    /// disagreeing until one of them changes the relationship again.
+    async fn transition_relationship(
+        &self,
+        user_id: &str,
+        target_id: &str,
+        (user_from, user_to): (RelationshipStatus, RelationshipStatus),
+        (target_from, target_to): (RelationshipStatus, RelationshipStatus),
+    ) -> Result<RelationshipStatus> {
+        if !self
+            .set_relationship(user_id, target_id, &user_from, &user_to)
+            .await?
+        {
+            return Err(Error::InvalidOperation);
+        }
+
+        match self
+            .set_relationship(target_id, user_id, &target_from, &target_to)
+            .await
+        {
+            Ok(true) => Ok(user_to),
+            outcome => {
+                self.set_relationship(user_id, target_id, &user_to, &user_from)
+                    .await?;
+                outcome.and(Err(Error::InvalidOperation))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl UserRepository for MongoDB {
+    async fn get_user_by_id(&self, id: &str) -> Result<User> {
+        if let Some(doc) = self
+            .col("users")
+            .find_one(
+                doc! {
+                    "_id": &id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find_one", "users", e).for_id(id))?
+        {
+            decode("users", doc)
+        } else {
+            Err(Error::UnknownUser)
+        }
+    }
+
+    async fn get_user_by_username(&self, username: &str) -> Result<User> {
+        if let Some(doc) = self
+            .col("users")
+            .find_one(
+                doc! {
+                    "username": username
+                },
+                FindOneOptions::builder()
+                    .collation(Collation::builder().locale("en").strength(2).build())
+                    .build(),
+            )
+            .await
+            .map_err(|e| Error::database("find_one", "users", e))?
+        {
+            decode("users", doc)
+        } else {
+            Err(Error::UnknownUser)
+        }
+    }
+
+    async fn get_user_id_by_bot_token(&self, token: &str) -> Result<String> {
+        let maybe_bot_doc = self
+            .col("bots")
+            .find_one(
+                doc! {
+                    "token": token
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find_one", "bots", e))?;
+        if let Some(doc) = maybe_bot_doc {
+            doc.get_str("_id")
+                .map(|id| id.to_string())
+                .map_err(|e| Error::InvalidDocument {
+                    collection: "bots",
+                    id: document_id(&doc),
+                    source: Some(Box::new(e)),
+                })
+        } else {
+            Err(Error::UnknownBot)
+        }
+    }
+
+    async fn get_users(&self, user_ids: Vec<&str>) -> Result<Vec<User>> {
+        let cursor = self.col("users")
+            .find(
+                doc! {
+                    "_id": {
+                        "$in": user_ids
+                    }
+                },
+                FindOptions::builder()
+                    .projection(
+                        doc! { "_id": 1, "username": 1, "avatar": 1, "badges": 1, "status": 1, "flags": 1, "bot": 1 },
+                    )
+                    .build(),
+            )
+            .await
+            .map_err(|e| Error::database("find", "users", e))?;
+        self.collect::<User, _>("users", cursor).await
+    }
+
+    async fn get_users_as_banned_users(&self, user_ids: Vec<&str>) -> Result<Vec<BannedUser>> {
+        let cursor = self
+            .col("users")
+            .find(
+                doc! {
+                    "_id": {
+                        "$in": user_ids
+                    }
+                },
+                FindOptions::builder()
+                    .projection(doc! {
+                        "username": 1,
+                        "avatar": 1
+                    })
+                    .build(),
+            )
+            .await
+            .map_err(|e| Error::database("find", "users", e))?;
+
+        self.collect::<BannedUser, _>("users", cursor).await
+    }
+
+    async fn get_bot_users_owned_by_user_id(&self, id: &str) -> Result<Vec<User>> {
+        let cursor = self
+            .col("users")
+            .find(
+                doc! {
+                    "bot.owner": id
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("find", "users", e))?;
+
+        self.collect::<User, _>("users", cursor).await
+    }
+
+    async fn get_mutual_friends_ids(
+        &self,
+        user_id_a: &str,
+        user_id_b: &str,
+    ) -> Result<Vec<String>> {
+        let cursor = self
+            .col("users")
+            .find(
+                doc! {
+                    "$and": [
+                        { "relations": { "$elemMatch": { "_id": user_id_a, "status": "Friend" } } },
+                        { "relations": { "$elemMatch": { "_id": user_id_b, "status": "Friend" } } }
+                    ]
+                },
+                FindOptions::builder().projection(doc! { "_id": 1 }).build(),
+            )
+            .await
+            .map_err(|e| Error::database("find", "users", e))?;
+
+        self.collect_ids("users", cursor).await
+    }
+
+    async fn add_user(&self, id: &str, username: &str) -> Result<()> {
+        self.col("users")
+            .insert_one(
+                doc! {
+                    "_id": id,
+                    "username": username
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("insert_one", "users", e))?;
+        Ok(())
+    }
+
+    async fn add_bot_user(&self, id: &str, username: &str, owner_id: &str) -> Result<()> {
+        self.col("users")
+            .insert_one(
+                doc! {
+                    "_id": id,
+                    "username": username,
+                    "bot": {
+                        "owner": owner_id
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("insert_one", "users", e))?;
+        Ok(())
+    }
+
+    async fn delete_user(&self, id: &str) -> Result<()> {
+        let username = format!("Deleted User {}", id);
+        self.col("users")
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$set": {
+                        "username": &username,
+                        "flags": 2
+                    },
+                    "$unset": {
+                        "avatar": 1,
+                        "status": 1,
+                        "profile": 1
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "users", e))?;
+        Ok(())
+    }
+
+    async fn update_username(&self, id: &str, new_username: &str) -> Result<()> {
+        self.col("users")
+            .update_one(
+                doc! { "_id": id },
+                doc! {
+                    "$set": {
+                        "username": new_username
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "users", e))?;
+        Ok(())
+    }
+
+    async fn make_user_already_in_relations_blocked(
+        &self,
+        origin_id: &str,
+        target_id: &str,
+    ) -> Result<()> {
+        self.col("users")
+            .update_one(
+                doc! {
+                    "_id": origin_id,
+                    "relations._id": target_id
+                },
+                doc! {
+                    "$set": {
+                        "relations.$.status": "Blocked"
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "users", e))?;
+        Ok(())
+    }
+
+    async fn make_user_already_in_relations_blocked_by(
+        &self,
+        target_id: &str,
+        origin_id: &str,
+    ) -> Result<()> {
+        self.col("users")
+            .update_one(
+                doc! {
+                    "_id": target_id,
+                    "relations._id": origin_id
+                },
+                doc! {
+                    "$set": {
+                        "relations.$.status": "BlockedOther"
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "users", e))?;
+        Ok(())
+    }
+
+    async fn make_user_not_in_relations_blocked(
+        &self,
+        origin_id: &str,
+        target_id: &str,
+    ) -> Result<()> {
+        self.col("users")
+            .update_one(
+                doc! {
+                    "_id": origin_id
+                },
+                doc! {
+                    "$push": {
+                        "relations": {
+                            "_id": target_id,
+                            "status": "Blocked"
+                        }
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "users", e))?;
+        Ok(())
+    }
+
+    async fn make_user_not_in_relations_blocked_by(
+        &self,
+        target_id: &str,
+        origin_id: &str,
+    ) -> Result<()> {
+        self.col("users")
+            .update_one(
+                doc! {
+                    "_id": target_id
+                },
+                doc! {
+                    "$push": {
+                        "relations": {
+                            "_id": origin_id,
+                            "status": "BlockedOther"
+                        }
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "users", e))?;
+        Ok(())
+    }
+
+    async fn apply_profile_changes(&self, id: &str, change_doc: Document) -> Result<()> {
+        self.col("users")
+            .update_one(doc! { "_id": id }, change_doc, None)
+            .await
+            .map_err(|e| Error::database("update_one", "users", e))?;
+        Ok(())
+    }
+
+    async fn remove_user_from_relations(&self, id: &str, target: &str) -> Result<()> {
+        self.col("users")
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$pull": {
+                        "relations": {
+                            "_id": target
+                        }
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "users", e))?;
+        Ok(())
+    }
+
+    async fn send_friend_request(
+        &self,
+        user_id: &str,
+        target_id: &str,
+    ) -> Result<RelationshipStatus> {
+        if user_id == target_id {
+            return Err(Error::InvalidOperation);
+        }
+
+        // Make sure the target exists and is a person before relating to it.
+        if self.get_user_by_id(target_id).await?.bot.is_some() {
+            return Err(Error::IsBot);
+        }
+
+        match self.get_relationship(user_id, target_id).await? {
+            RelationshipStatus::User => Err(Error::InvalidOperation),
+            RelationshipStatus::Friend => Err(Error::AlreadyFriends),
+            RelationshipStatus::Outgoing => Err(Error::AlreadySentRequest),
+            RelationshipStatus::Blocked => Err(Error::Blocked),
+            RelationshipStatus::BlockedOther => Err(Error::BlockedByOther),
+            RelationshipStatus::Incoming => self.accept_friend_request(user_id, target_id).await,
+            RelationshipStatus::None => {
+                self.transition_relationship(
+                    user_id,
+                    target_id,
+                    (RelationshipStatus::None, RelationshipStatus::Outgoing),
+                    (RelationshipStatus::None, RelationshipStatus::Incoming),
+                )
+                .await
+            }
+        }
+    }
+
+    async fn accept_friend_request(
+        &self,
+        user_id: &str,
+        target_id: &str,
+    ) -> Result<RelationshipStatus> {
+        match self.get_relationship(user_id, target_id).await? {
+            RelationshipStatus::Incoming => {
+                self.transition_relationship(
+                    user_id,
+                    target_id,
+                    (RelationshipStatus::Incoming, RelationshipStatus::Friend),
+                    (RelationshipStatus::Outgoing, RelationshipStatus::Friend),
+                )
+                .await
+            }
+            RelationshipStatus::Friend => Err(Error::AlreadyFriends),
+            RelationshipStatus::Blocked => Err(Error::Blocked),
+            RelationshipStatus::BlockedOther => Err(Error::BlockedByOther),
+            _ => Err(Error::NoEffect),
+        }
+    }
+
+    async fn deny_friend_request(
+        &self,
+        user_id: &str,
+        target_id: &str,
+    ) -> Result<RelationshipStatus> {
+        match self.get_relationship(user_id, target_id).await? {
+            status @ RelationshipStatus::Incoming | status @ RelationshipStatus::Outgoing => {
+                self.transition_relationship(
+                    user_id,
+                    target_id,
+                    (status.clone(), RelationshipStatus::None),
+                    (status.reciprocal(), RelationshipStatus::None),
+                )
+                .await
+            }
+            _ => Err(Error::NoEffect),
+        }
+    }
+
+    async fn remove_friend(&self, user_id: &str, target_id: &str) -> Result<RelationshipStatus> {
+        match self.get_relationship(user_id, target_id).await? {
+            RelationshipStatus::Friend => {
+                self.transition_relationship(
+                    user_id,
+                    target_id,
+                    (RelationshipStatus::Friend, RelationshipStatus::None),
+                    (RelationshipStatus::Friend, RelationshipStatus::None),
+                )
+                .await
+            }
+            _ => Err(Error::NotFriends),
+        }
+    }
+
+    async fn block_user(&self, user_id: &str, target_id: &str) -> Result<RelationshipStatus> {
+        if user_id == target_id {
+            return Err(Error::InvalidOperation);
+        }
+
+        if self.get_user_by_id(target_id).await?.bot.is_some() {
+            return Err(Error::IsBot);
+        }
+
+        match self.get_relationship(user_id, target_id).await? {
+            RelationshipStatus::User => Err(Error::InvalidOperation),
+            RelationshipStatus::Blocked => Err(Error::NoEffect),
+            // The target already blocked us, their side stays as it is.
+            RelationshipStatus::BlockedOther => {
+                self.transition_relationship(
+                    user_id,
+                    target_id,
+                    (
+                        RelationshipStatus::BlockedOther,
+                        RelationshipStatus::Blocked,
+                    ),
+                    (RelationshipStatus::Blocked, RelationshipStatus::Blocked),
+                )
+                .await
+            }
+            status => {
+                self.transition_relationship(
+                    user_id,
+                    target_id,
+                    (status.clone(), RelationshipStatus::Blocked),
+                    (status.reciprocal(), RelationshipStatus::BlockedOther),
+                )
+                .await
+            }
+        }
+    }
+
+    async fn unblock_user(&self, user_id: &str, target_id: &str) -> Result<RelationshipStatus> {
+        match self.get_relationship(user_id, target_id).await? {
+            RelationshipStatus::Blocked => {
+                // If the block is mutual, we remain blocked by the target.
+                if self.get_relationship(target_id, user_id).await? == RelationshipStatus::Blocked {
+                    self.transition_relationship(
+                        user_id,
+                        target_id,
+                        (
+                            RelationshipStatus::Blocked,
+                            RelationshipStatus::BlockedOther,
+                        ),
+                        (RelationshipStatus::Blocked, RelationshipStatus::Blocked),
+                    )
+                    .await
+                } else {
+                    self.transition_relationship(
+                        user_id,
+                        target_id,
+                        (RelationshipStatus::Blocked, RelationshipStatus::None),
+                        (RelationshipStatus::BlockedOther, RelationshipStatus::None),
+                    )
+                    .await
+                }
+            }
+            _ => Err(Error::NoEffect),
+        }
+    }
+
+    async fn delete_all_relations(&self, id: &str) -> Result<()> {
+        self.col("users")
+            .update_many(
+                doc! {
+                    "relations._id": id
+                },
+                doc! {
+                    "$pull": {
+                        "relations": {
+                            "_id": id
+                        }
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_many", "users", e))?;
+
+        self.col("users")
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$unset": {
+                        "relations": 1
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Error::database("update_one", "users", e).for_id(id))?;
+        Ok(())
+    }
+}
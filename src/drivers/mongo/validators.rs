@@ -0,0 +1,179 @@
+use super::{MongoDB, ValidationLevel};
+use crate::entities::{
+    Ban, Bot, Channel, ChannelUnread, File, Invite, Job, Member, Message, Server, User,
+};
+use crate::util::result::{Error, Result};
+use log::info;
+use mongodb::bson::{doc, to_document, Document};
+use schemars::gen::SchemaSettings;
+use schemars::JsonSchema;
+use serde_json::{json, Map, Value};
+
+/// Schema for each collection that stores an entity.
+pub fn schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("users", schema_for::<User>()),
+        ("channels", schema_for::<Channel>()),
+        ("servers", schema_for::<Server>()),
+        ("server_members", schema_for::<Member>()),
+        ("server_bans", schema_for::<Ban>()),
+        ("channel_invites", schema_for::<Invite>()),
+        ("channel_unreads", schema_for::<ChannelUnread>()),
+        ("messages", schema_for::<Message>()),
+        ("attachments", schema_for::<File>()),
+        ("bots", schema_for::<Bot>()),
+        ("jobs", schema_for::<Job>()),
+    ]
+}
+
+/// Install the entity schemas as collection validators, or remove them if
+/// `level` is `Off`.
+pub async fn apply(db: &MongoDB, level: ValidationLevel) -> Result<()> {
+    info!("Applying collection validators at level {:?}.", level);
+
+    for (collection, schema) in schemas() {
+        let command = match level {
+            ValidationLevel::Off => doc! {
+                "collMod": db.collection_name(collection),
+                "validator": {},
+                "validationLevel": "off"
+            },
+            ValidationLevel::Warn | ValidationLevel::Enforce => {
+                let action = match level {
+                    ValidationLevel::Enforce => "error",
+                    _ => "warn",
+                };
+
+                doc! {
+                    "collMod": db.collection_name(collection),
+                    "validator": { "$jsonSchema": to_mongo_schema(collection, &schema)? },
+                    "validationLevel": "strict",
+                    "validationAction": action
+                }
+            }
+        };
+
+        db.revolt
+            .run_command(command, None)
+            .await
+            .map_err(|e| Error::database("coll_mod", collection, e))?;
+    }
+
+    Ok(())
+}
+
+fn schema_for<T: JsonSchema>() -> Value {
+    let schema = SchemaSettings::draft07()
+        .with(|settings| settings.option_add_null_type = true)
+        .into_generator()
+        .into_root_schema_for::<T>();
+
+    json!(schema)
+}
+
+/// MongoDB only understands a subset of JSON schema: no references,
+/// `bsonType` instead of the `integer` type, and no annotations such as
+/// `format`.
+fn to_mongo_schema(collection: &'static str, schema: &Value) -> Result<Document> {
+    let empty = Map::new();
+    let definitions = schema
+        .get("definitions")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+
+    to_document(&convert(schema, definitions))
+        .map_err(|e| Error::database("to_document", collection, e))
+}
+
+fn convert(schema: &Value, definitions: &Map<String, Value>) -> Value {
+    let map = match schema {
+        Value::Object(map) => map,
+        Value::Array(items) => {
+            return Value::Array(
+                items
+                    .iter()
+                    .map(|item| convert(item, definitions))
+                    .collect(),
+            )
+        }
+        other => return other.clone(),
+    };
+
+    if let Some(reference) = map.get("$ref").and_then(Value::as_str) {
+        let name = reference.trim_start_matches("#/definitions/");
+        return definitions
+            .get(name)
+            .map(|definition| convert(definition, definitions))
+            .unwrap_or_else(|| json!({}));
+    }
+
+    let mut converted = Map::new();
+    for (key, value) in map {
+        match key.as_str() {
+            "$schema" | "title" | "definitions" | "format" | "default" | "examples" => {}
+            // Types set by hand, such as dates, take precedence.
+            "type" if map.contains_key("bsonType") => {}
+            "type" => {
+                converted.insert("bsonType".to_string(), bson_type(value));
+            }
+            "const" => {
+                converted.insert("enum".to_string(), json!([value]));
+            }
+            "enum" | "required" | "description" | "bsonType" => {
+                converted.insert(key.clone(), value.clone());
+            }
+            // Maps from names to schemas, where the names are not keywords.
+            "properties" | "patternProperties" => {
+                let properties = value
+                    .as_object()
+                    .map(|properties| {
+                        properties
+                            .iter()
+                            .map(|(name, schema)| (name.clone(), convert(schema, definitions)))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                converted.insert(key.clone(), Value::Object(properties));
+            }
+            _ => {
+                converted.insert(key.clone(), convert(value, definitions));
+            }
+        }
+    }
+
+    Value::Object(converted)
+}
+
+fn bson_type(value: &Value) -> Value {
+    let mut types: Vec<&str> = vec![];
+    let names: Vec<&str> = match value {
+        Value::String(name) => vec![name.as_str()],
+        Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    };
+
+    for name in names {
+        let mapped: &[&str] = match name {
+            "integer" => &["int", "long"],
+            "number" => &["int", "long", "double", "decimal"],
+            "boolean" => &["bool"],
+            "string" => &["string"],
+            "object" => &["object"],
+            "array" => &["array"],
+            "null" => &["null"],
+            _ => &[],
+        };
+
+        for name in mapped {
+            if !types.contains(name) {
+                types.push(*name);
+            }
+        }
+    }
+
+    match types.as_slice() {
+        [single] => json!(single),
+        _ => json!(types),
+    }
+}
@@ -0,0 +1,241 @@
+use crate::entities::{
+    Ban, Bot, Channel, ChannelUnread, File, Invite, Member, Message, Server, User,
+};
+use mongodb::bson::{Bson, Document};
+use serde::{Deserialize, Serialize};
+
+/// Kinds of entity in a full archive, named independently of how any driver
+/// stores them.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    Account,
+    User,
+    UserSettings,
+    Bot,
+    Server,
+    Member,
+    Ban,
+    Channel,
+    Invite,
+    Unread,
+    Message,
+    Attachment,
+}
+
+impl EntityKind {
+    /// Every kind, in the order they are exported.
+    pub const ALL: &'static [EntityKind] = &[
+        EntityKind::Account,
+        EntityKind::User,
+        EntityKind::UserSettings,
+        EntityKind::Bot,
+        EntityKind::Server,
+        EntityKind::Member,
+        EntityKind::Ban,
+        EntityKind::Channel,
+        EntityKind::Invite,
+        EntityKind::Unread,
+        EntityKind::Message,
+        EntityKind::Attachment,
+    ];
+}
+
+/// A single stored entity, as moved between databases by an archive.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind", content = "entity", rename_all = "snake_case")]
+pub enum ArchiveEntity {
+    /// Accounts belong to the authentication service, so their fields are
+    /// carried as they are.
+    Account(Document),
+    User(User),
+    UserSettings {
+        #[serde(rename = "_id")]
+        id: String,
+        settings: Document,
+    },
+    Bot(Bot),
+    Server(Server),
+    Member(Member),
+    Ban(Ban),
+    Channel(Channel),
+    Invite(Invite),
+    Unread(ChannelUnread),
+    Message(Message),
+    Attachment(File),
+}
+
+/// One page of entities of a single kind.
+#[derive(Debug, Default)]
+pub struct ArchivePage {
+    pub entities: Vec<ArchiveEntity>,
+    /// Key of the last entity read, including any the read policy left out
+    /// of `entities`. Pass it back to get the next page; `None` once there
+    /// are no more.
+    pub last: Option<Bson>,
+}
+
+#[cfg(test)]
+impl ArchiveEntity {
+    /// One entity of every kind, with as many optional fields filled in as
+    /// possible, for round-trip tests.
+    pub(crate) fn samples() -> Vec<ArchiveEntity> {
+        use chrono::{TimeZone, Utc};
+        use mongodb::bson::{doc, from_document};
+
+        let at = Utc.timestamp_millis(1_620_000_000_123);
+        let file = doc! {
+            "_id": "01F7ZSBSFHQ8TA81725KQCSDDF",
+            "tag": "attachments",
+            "filename": "cat.png",
+            "metadata": { "type": "Image", "width": 640, "height": 480 },
+            "content_type": "image/png",
+            "size": 5_000_000_000i64,
+            "message_id": "01F7ZSBSFHQ8TA81725KQCSDDM"
+        };
+
+        vec![
+            doc! {
+                "kind": "account",
+                "entity": {
+                    "_id": "01F7ZSBSFHQ8TA81725KQCSDDU",
+                    "email": "alice@example.com",
+                    "password": "$argon2id$v=19$m=4096,t=3,p=1$c2FsdA$aGFzaA",
+                    "verification": { "status": "Verified" },
+                    "created_at": at
+                }
+            },
+            doc! {
+                "kind": "user",
+                "entity": {
+                    "_id": "01F7ZSBSFHQ8TA81725KQCSDDU",
+                    "username": "alice",
+                    "relations": [{ "_id": "01F7ZSBSFHQ8TA81725KQCSDDV", "status": "Friend" }],
+                    "badges": 256,
+                    "status": { "text": "Away", "presence": "Idle" },
+                    "profile": { "content": "Hello" },
+                    "flags": 0
+                }
+            },
+            doc! {
+                "kind": "user_settings",
+                "entity": {
+                    "_id": "01F7ZSBSFHQ8TA81725KQCSDDU",
+                    "settings": { "theme": [1_620_000_000_123i64, "{\"dark\":true}"] }
+                }
+            },
+            doc! {
+                "kind": "bot",
+                "entity": {
+                    "_id": "01F7ZSBSFHQ8TA81725KQCSDDB",
+                    "owner": "01F7ZSBSFHQ8TA81725KQCSDDU",
+                    "token": "token",
+                    "public": true,
+                    "interactions_url": "https://example.com/interactions"
+                }
+            },
+            doc! {
+                "kind": "server",
+                "entity": {
+                    "_id": "01F7ZSBSFHQ8TA81725KQCSDDS",
+                    "owner": "01F7ZSBSFHQ8TA81725KQCSDDU",
+                    "name": "Server",
+                    "description": "A server",
+                    "channels": ["01F7ZSBSFHQ8TA81725KQCSDDC"],
+                    "categories": [{
+                        "id": "general",
+                        "title": "General",
+                        "channels": ["01F7ZSBSFHQ8TA81725KQCSDDC"]
+                    }],
+                    "system_messages": { "user_joined": "01F7ZSBSFHQ8TA81725KQCSDDC" },
+                    "roles": {
+                        "01F7ZSBSFHQ8TA81725KQCSDDR": {
+                            "name": "Moderator",
+                            "permissions": [1, 2],
+                            "colour": "#ff0000",
+                            "hoist": true,
+                            "rank": 1i64
+                        }
+                    },
+                    "default_permissions": [3, 4],
+                    "nsfw": true
+                }
+            },
+            doc! {
+                "kind": "member",
+                "entity": {
+                    "_id": {
+                        "server": "01F7ZSBSFHQ8TA81725KQCSDDS",
+                        "user": "01F7ZSBSFHQ8TA81725KQCSDDU"
+                    },
+                    "nickname": "Al",
+                    "roles": ["01F7ZSBSFHQ8TA81725KQCSDDR"]
+                }
+            },
+            doc! {
+                "kind": "ban",
+                "entity": {
+                    "_id": {
+                        "server": "01F7ZSBSFHQ8TA81725KQCSDDS",
+                        "user": "01F7ZSBSFHQ8TA81725KQCSDDV"
+                    },
+                    "reason": "Spam"
+                }
+            },
+            doc! {
+                "kind": "channel",
+                "entity": {
+                    "_id": "01F7ZSBSFHQ8TA81725KQCSDDC",
+                    "channel_type": "TextChannel",
+                    "server": "01F7ZSBSFHQ8TA81725KQCSDDS",
+                    "name": "general",
+                    "description": "Talk here",
+                    "last_message": "01F7ZSBSFHQ8TA81725KQCSDDM",
+                    "default_permissions": 5,
+                    "role_permissions": { "01F7ZSBSFHQ8TA81725KQCSDDR": 6 },
+                    "nsfw": false
+                }
+            },
+            doc! {
+                "kind": "invite",
+                "entity": {
+                    "_id": "invite",
+                    "type": "Server",
+                    "server": "01F7ZSBSFHQ8TA81725KQCSDDS",
+                    "creator": "01F7ZSBSFHQ8TA81725KQCSDDU",
+                    "channel": "01F7ZSBSFHQ8TA81725KQCSDDC"
+                }
+            },
+            doc! {
+                "kind": "unread",
+                "entity": {
+                    "_id": {
+                        "channel": "01F7ZSBSFHQ8TA81725KQCSDDC",
+                        "user": "01F7ZSBSFHQ8TA81725KQCSDDU"
+                    },
+                    "last_id": "01F7ZSBSFHQ8TA81725KQCSDDM",
+                    "mentions": ["01F7ZSBSFHQ8TA81725KQCSDDM"]
+                }
+            },
+            doc! {
+                "kind": "message",
+                "entity": {
+                    "_id": "01F7ZSBSFHQ8TA81725KQCSDDM",
+                    "nonce": "nonce",
+                    "channel": "01F7ZSBSFHQ8TA81725KQCSDDC",
+                    "author": "01F7ZSBSFHQ8TA81725KQCSDDU",
+                    "content": "Hello",
+                    "attachments": [file.clone()],
+                    "edited": at,
+                    "mentions": ["01F7ZSBSFHQ8TA81725KQCSDDV"],
+                    "replies": ["01F7ZSBSFHQ8TA81725KQCSDDL"],
+                    "deleted": { "at": at, "by": "01F7ZSBSFHQ8TA81725KQCSDDU" }
+                }
+            },
+            doc! { "kind": "attachment", "entity": file },
+        ]
+        .into_iter()
+        .map(|entity| from_document(entity).unwrap())
+        .collect()
+    }
+}
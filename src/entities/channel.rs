@@ -14,9 +14,10 @@ use mongodb::{
     options::FindOptions,
 };
 use rocket::serde::json::Value;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct LastMessage {
     #[serde(rename = "_id")]
     id: String,
@@ -24,7 +25,7 @@ pub struct LastMessage {
     short: String,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(tag = "channel_type")]
 pub enum Channel {
     SavedMessages {
@@ -111,9 +112,6 @@ pub enum Channel {
     },
 }
 
-/*
-
-
 impl Channel {
     pub fn id(&self) -> &str {
         match self {
@@ -124,6 +122,10 @@ impl Channel {
             | Channel::VoiceChannel { id, .. } => id,
         }
     }
+}
+
+/*
+impl Channel {
     pub fn has_messaging(&self) -> Result<()> {
         match self {
             Channel::SavedMessages { .. }
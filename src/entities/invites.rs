@@ -1,6 +1,7 @@
 use mongodb::bson::doc;
 use mongodb::bson::from_document;
 use mongodb::bson::to_document;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /*
@@ -9,7 +10,7 @@ use crate::util::result::Error;
 use crate::util::result::Result;
 */
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(tag = "type")]
 pub enum Invite {
     Server {
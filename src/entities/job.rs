@@ -0,0 +1,39 @@
+use super::message::BsonDate;
+use mongodb::bson::DateTime;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// What happens to the bots of a deleted account.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum BotDisposal {
+    Delete,
+    Transfer { owner: String },
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum JobKind {
+    DeleteAccount {
+        user: String,
+        bots: BotDisposal,
+        scrub_messages: bool,
+    },
+}
+
+/// Long-running work that is stored so it can resume after a restart.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct Job {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub kind: JobKind,
+    /// Index of the first step that has not completed.
+    pub step: i32,
+    /// Process currently running the job, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// When the owner's claim lapses unless it is renewed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<BsonDate>")]
+    pub expires_at: Option<DateTime>,
+}
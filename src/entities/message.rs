@@ -17,23 +17,26 @@ use mongodb::{
 use crate::entities::microservice::january::Embed;
 use rocket::serde::json::Value;
 use rocket::FromFormField;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{Schema, SchemaObject};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use ulid::Ulid;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct MemberCompositeKey {
     pub server: String,
     pub user: String,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Ban {
     #[serde(rename = "_id")]
     pub id: MemberCompositeKey,
     pub reason: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Member {
     #[serde(rename = "_id")]
     pub id: MemberCompositeKey,
@@ -53,6 +56,29 @@ pub enum Sort {
     Latest,
     Oldest,
 }
+
+/// Selection of messages within a channel used by bulk operations.
+/// Every criterion that is set has to match.
+#[derive(Debug, Clone, Default)]
+pub struct MessageFilter {
+    pub ids: Option<Vec<String>>,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub author: Option<String>,
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+    pub until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl MessageFilter {
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_none()
+            && self.before.is_none()
+            && self.after.is_none()
+            && self.author.is_none()
+            && self.since.is_none()
+            && self.until.is_none()
+    }
+}
 /*
 use web_push::{
     ContentEncoding, SubscriptionInfo, VapidSignatureBuilder, WebPushClient, WebPushMessageBuilder,
@@ -116,7 +142,7 @@ impl PushNotification {
 }
 */
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(tag = "type")]
 pub enum SystemMessage {
     #[serde(rename = "text")]
@@ -158,7 +184,7 @@ impl Into<String> for SystemMessage {
     }
 }
 */
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(untagged)]
 pub enum Content {
     Text(String),
@@ -179,7 +205,33 @@ impl Content {
     }
 }*/
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Stands in for `DateTime` in schemas, since it is stored as a BSON date
+/// rather than anything JSON schema can describe.
+pub(crate) struct BsonDate;
+
+impl JsonSchema for BsonDate {
+    fn schema_name() -> String {
+        "DateTime".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        let mut schema = SchemaObject::default();
+        schema
+            .extensions
+            .insert("bsonType".to_string(), Value::from("date"));
+        schema.into()
+    }
+}
+
+/// Present on messages which were soft-deleted and may still be restored.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct MessageDeletion {
+    #[schemars(with = "BsonDate")]
+    pub at: DateTime,
+    pub by: String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Message {
     #[serde(rename = "_id")]
     pub id: String,
@@ -192,6 +244,7 @@ pub struct Message {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub attachments: Option<Vec<File>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<BsonDate>")]
     pub edited: Option<DateTime>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub embeds: Option<Vec<Embed>>,
@@ -199,6 +252,18 @@ pub struct Message {
     pub mentions: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub replies: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted: Option<MessageDeletion>,
+}
+
+/// One page of messages.
+#[derive(Debug, Default)]
+pub struct MessagePage {
+    pub messages: Vec<Message>,
+    /// ID of the last message read, including any the read policy left out
+    /// of `messages`. Pass it back to get the next page; `None` once there
+    /// are no more.
+    pub last: Option<String>,
 }
 
 /*impl Message {
@@ -1,11 +1,12 @@
 use mongodb::bson::{doc, from_document};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 // use crate::database::*;
 use crate::util::result::{Error, Result};
 // use crate::util::variables::AUTUMN_URL;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(tag = "type")]
 enum Metadata {
     File,
@@ -15,7 +16,7 @@ enum Metadata {
     Audio,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct File {
     #[serde(rename = "_id")]
     pub id: String,
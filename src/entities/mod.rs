@@ -5,13 +5,16 @@ mod invites;
 mod server;
 mod sync;
  */
+mod archive;
 mod bots;
 mod channel;
 mod file;
 mod invites;
+mod job;
 mod message;
 pub(crate) mod microservice;
 mod server;
+mod sync;
 mod user;
 /*
 
@@ -26,10 +29,13 @@ pub use server::*;
 pub use sync::*;
 
  */
+pub use archive::*;
 pub use bots::*;
 pub use channel::*;
 pub use file::*;
 pub use invites::*;
+pub use job::*;
 pub use message::*;
 pub use server::*;
+pub use sync::*;
 pub use user::*;
@@ -8,6 +8,7 @@ use mongodb::bson::to_document;
 use mongodb::bson::Document;
 use mongodb::bson::{doc, Bson};
 use rocket::serde::json::Value;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use ulid::Ulid;
 /*
@@ -40,7 +41,7 @@ pub fn if_false(t: &bool) -> bool {
     !t
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Role {
     pub name: String,
     pub permissions: PermissionTuple,
@@ -52,7 +53,7 @@ pub struct Role {
     pub rank: i64,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Category {
     pub id: String,
     pub title: String,
@@ -66,7 +67,7 @@ pub struct Ban {
     pub reason: Option<String>,
 }
 */
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct SystemMessageChannels {
     pub user_joined: Option<String>,
     pub user_left: Option<String>,
@@ -80,7 +81,7 @@ pub enum RemoveMember {
     Ban,
 }
 */
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Server {
     #[serde(rename = "_id")]
     pub id: String,
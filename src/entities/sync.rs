@@ -1,15 +1,16 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 pub type UserSettings = HashMap<String, (i64, String)>;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct ChannelCompositeKey {
     pub channel: String,
     pub user: String,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct ChannelUnread {
     #[serde(rename = "_id")]
     pub id: ChannelCompositeKey,
@@ -17,3 +18,23 @@ pub struct ChannelUnread {
     pub last_id: Option<String>,
     pub mentions: Option<Vec<String>>,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChannelUnreadCount {
+    pub channel: String,
+    pub unread: u64,
+    pub mentions: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UserSettingsEntry {
+    pub key: String,
+    pub timestamp: i64,
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct UserSettingsSyncResult {
+    pub accepted: Vec<String>,
+    pub rejected: Vec<String>,
+}
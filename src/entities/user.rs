@@ -9,6 +9,7 @@ use mongodb::{
     options::FindOptions,
 };
 use num_enum::TryFromPrimitive;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::ops;
 use ulid::Ulid;
@@ -37,7 +38,7 @@ pub struct BannedUser {
     avatar: Option<File>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub enum RelationshipStatus {
     None,
     User,
@@ -48,14 +49,27 @@ pub enum RelationshipStatus {
     BlockedOther,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl RelationshipStatus {
+    /// The status the other side of a relationship sees.
+    pub fn reciprocal(&self) -> RelationshipStatus {
+        match self {
+            RelationshipStatus::Outgoing => RelationshipStatus::Incoming,
+            RelationshipStatus::Incoming => RelationshipStatus::Outgoing,
+            RelationshipStatus::Blocked => RelationshipStatus::BlockedOther,
+            RelationshipStatus::BlockedOther => RelationshipStatus::Blocked,
+            other => other.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Relationship {
     #[serde(rename = "_id")]
     pub id: String,
     pub status: RelationshipStatus,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub enum Presence {
     Online,
     Idle,
@@ -63,7 +77,7 @@ pub enum Presence {
     Invisible,
 }
 
-#[derive(Validate, Serialize, Deserialize, Debug, Clone)]
+#[derive(Validate, Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct UserStatus {
     #[validate(length(min = 1, max = 128))]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -72,7 +86,7 @@ pub struct UserStatus {
     pub presence: Option<Presence>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct UserProfile {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
@@ -93,13 +107,13 @@ pub enum Badges {
 
 impl_op_ex_commutative!(+ |a: &i32, b: &Badges| -> i32 { *a | *b as i32 });
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct BotInformation {
     owner: String,
 }
 
 // When changing this struct, update notifications/payload.rs#113
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct User {
     #[serde(rename = "_id")]
     pub id: String,
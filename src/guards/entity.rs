@@ -0,0 +1,178 @@
+use crate::entities::{Bot, Channel, Invite, Member, Message, Server, User};
+use crate::guards::Ref;
+use crate::util::result::{Error, Result};
+use crate::Database;
+
+use rocket::async_trait;
+use rocket::request::{FromRequest, Outcome, Request};
+use std::ops::Deref;
+
+/// Entities that can be looked up from a single ID.
+#[async_trait]
+pub trait FromId: Sized + Send {
+    async fn from_id(db: &Database, id: &Ref) -> Result<Self>;
+}
+
+#[async_trait]
+impl FromId for User {
+    async fn from_id(db: &Database, id: &Ref) -> Result<Self> {
+        id.fetch_user(db).await
+    }
+}
+
+#[async_trait]
+impl FromId for Channel {
+    async fn from_id(db: &Database, id: &Ref) -> Result<Self> {
+        id.fetch_channel(db).await
+    }
+}
+
+#[async_trait]
+impl FromId for Server {
+    async fn from_id(db: &Database, id: &Ref) -> Result<Self> {
+        id.fetch_server(db).await
+    }
+}
+
+#[async_trait]
+impl FromId for Invite {
+    async fn from_id(db: &Database, id: &Ref) -> Result<Self> {
+        id.fetch_invite(db).await
+    }
+}
+
+#[async_trait]
+impl FromId for Bot {
+    async fn from_id(db: &Database, id: &Ref) -> Result<Self> {
+        id.fetch_bot(db).await
+    }
+}
+
+/// Request guard resolving the `N`th path segment after the mount point
+/// into an entity, e.g. `Fetched<Channel, 1>` for `/channels/<id>`.
+pub struct Fetched<T, const N: usize>(pub T);
+
+impl<T, const N: usize> Fetched<T, N> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T, const N: usize> Deref for Fetched<T, N> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+/// Request guard resolving the server ID in segment `SERVER` and the user ID
+/// in segment `USER` into that user's membership of the server.
+pub struct FetchedMember<const SERVER: usize, const USER: usize>(pub Member);
+
+impl<const SERVER: usize, const USER: usize> FetchedMember<SERVER, USER> {
+    pub fn into_inner(self) -> Member {
+        self.0
+    }
+}
+
+impl<const SERVER: usize, const USER: usize> Deref for FetchedMember<SERVER, USER> {
+    type Target = Member;
+
+    fn deref(&self) -> &Member {
+        &self.0
+    }
+}
+
+/// Request guard resolving the channel ID in segment `CHANNEL` and the
+/// message ID in segment `MESSAGE` into a message, failing if the message
+/// belongs to another channel.
+pub struct FetchedMessage<const CHANNEL: usize, const MESSAGE: usize>(pub Message);
+
+impl<const CHANNEL: usize, const MESSAGE: usize> FetchedMessage<CHANNEL, MESSAGE> {
+    pub fn into_inner(self) -> Message {
+        self.0
+    }
+}
+
+impl<const CHANNEL: usize, const MESSAGE: usize> Deref for FetchedMessage<CHANNEL, MESSAGE> {
+    type Target = Message;
+
+    fn deref(&self) -> &Message {
+        &self.0
+    }
+}
+
+fn database<'r>(request: &'r Request<'_>) -> Result<&'r Database> {
+    request
+        .rocket()
+        .state::<Database>()
+        .ok_or(Error::InternalError)
+}
+
+fn segment(request: &Request<'_>, n: usize) -> Result<Ref> {
+    let id = request.routed_segment(n).ok_or(Error::InternalError)?;
+    Ref::from(id.to_string())
+}
+
+fn outcome<T>(result: Result<T>) -> Outcome<T, Error> {
+    match result {
+        Ok(value) => Outcome::Success(value),
+        Err(error) => Outcome::Failure((error.status(), error)),
+    }
+}
+
+#[async_trait]
+impl<'r, T: FromId, const N: usize> FromRequest<'r> for Fetched<T, N> {
+    type Error = Error;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        outcome(
+            async {
+                let db = database(request)?;
+                let id = segment(request, N)?;
+                T::from_id(db, &id).await.map(Fetched)
+            }
+            .await,
+        )
+    }
+}
+
+#[async_trait]
+impl<'r, const SERVER: usize, const USER: usize> FromRequest<'r> for FetchedMember<SERVER, USER> {
+    type Error = Error;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        outcome(
+            async {
+                let db = database(request)?;
+                let server = segment(request, SERVER)?;
+                let user = segment(request, USER)?;
+                user.fetch_member(db, &server.id).await.map(FetchedMember)
+            }
+            .await,
+        )
+    }
+}
+
+#[async_trait]
+impl<'r, const CHANNEL: usize, const MESSAGE: usize> FromRequest<'r>
+    for FetchedMessage<CHANNEL, MESSAGE>
+{
+    type Error = Error;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        outcome(
+            async {
+                let db = database(request)?;
+                let channel = segment(request, CHANNEL)?.fetch_channel(db).await?;
+                let message = segment(request, MESSAGE)?;
+                message
+                    .fetch_message(db, &channel)
+                    .await
+                    .map(FetchedMessage)
+            }
+            .await,
+        )
+    }
+}
@@ -1,3 +1,5 @@
+pub mod entity;
 pub mod reference;
 
+pub use entity::{Fetched, FetchedMember, FetchedMessage, FromId};
 pub use reference::Ref;
@@ -1,9 +1,12 @@
-use crate::database::*;
+use crate::entities::{Ban, Bot, Channel, Invite, Member, Message, Server, User};
 use crate::util::result::{Error, Result};
+use crate::{
+    BanRepository, BotRepository, ChannelRepository, Database, InviteRepository, MemberRepository,
+    MessageRepository, ServerRepository, UserRepository,
+};
 
-use mongodb::bson::{doc, from_document};
 use rocket::request::FromParam;
-use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde::{Deserialize, Serialize};
 use validator::Validate;
 
 #[derive(Validate, Serialize, Deserialize)]
@@ -24,96 +27,37 @@ impl Ref {
         Ok(r)
     }
 
-    async fn fetch<T: DeserializeOwned>(&self, collection: &'static str) -> Result<T> {
-        let doc = get_collection(&collection)
-            .find_one(
-                doc! {
-                    "_id": &self.id
-                },
-                None,
-            )
-            .await
-            .map_err(|_| Error::DatabaseError {
-                operation: "find_one",
-                with: &collection,
-            })?
-            .ok_or_else(|| Error::NotFound)?;
-
-        Ok(from_document::<T>(doc).map_err(|_| Error::DatabaseError {
-            operation: "from_document",
-            with: &collection,
-        })?)
+    pub async fn fetch_user(&self, db: &Database) -> Result<User> {
+        db.get_user_by_id(&self.id).await
     }
 
-    pub async fn fetch_user(&self) -> Result<User> {
-        self.fetch("users").await
+    pub async fn fetch_channel(&self, db: &Database) -> Result<Channel> {
+        db.get_channel_by_id(&self.id).await
     }
 
-    pub async fn fetch_channel(&self) -> Result<Channel> {
-        self.fetch("channels").await
+    pub async fn fetch_server(&self, db: &Database) -> Result<Server> {
+        db.get_server_by_id(&self.id).await
     }
 
-    pub async fn fetch_server(&self) -> Result<Server> {
-        self.fetch("servers").await
+    pub async fn fetch_invite(&self, db: &Database) -> Result<Invite> {
+        db.get_invite_by_id(&self.id).await
     }
 
-    pub async fn fetch_invite(&self) -> Result<Invite> {
-        self.fetch("channel_invites").await
+    pub async fn fetch_bot(&self, db: &Database) -> Result<Bot> {
+        db.get_bot_by_id(&self.id).await
     }
 
-    pub async fn fetch_bot(&self) -> Result<Bot> {
-        self.fetch("bots").await
+    pub async fn fetch_member(&self, db: &Database, server: &str) -> Result<Member> {
+        db.get_server_member(server, &self.id).await
     }
 
-    pub async fn fetch_member(&self, server: &str) -> Result<Member> {
-        let doc = get_collection("server_members")
-            .find_one(
-                doc! {
-                    "_id.user": &self.id,
-                    "_id.server": server
-                },
-                None,
-            )
-            .await
-            .map_err(|_| Error::DatabaseError {
-                operation: "find_one",
-                with: "server_member",
-            })?
-            .ok_or_else(|| Error::NotFound)?;
-
-        Ok(
-            from_document::<Member>(doc).map_err(|_| Error::DatabaseError {
-                operation: "from_document",
-                with: "server_member",
-            })?,
-        )
+    pub async fn fetch_ban(&self, db: &Database, server: &str) -> Result<Ban> {
+        db.get_ban(server, &self.id).await
     }
 
-    pub async fn fetch_ban(&self, server: &str) -> Result<Ban> {
-        let doc = get_collection("server_bans")
-            .find_one(
-                doc! {
-                    "_id.user": &self.id,
-                    "_id.server": server
-                },
-                None,
-            )
-            .await
-            .map_err(|_| Error::DatabaseError {
-                operation: "find_one",
-                with: "server_ban",
-            })?
-            .ok_or_else(|| Error::NotFound)?;
-
-        Ok(from_document::<Ban>(doc).map_err(|_| Error::DatabaseError {
-            operation: "from_document",
-            with: "server_ban",
-        })?)
-    }
-
-    pub async fn fetch_message(&self, channel: &Channel) -> Result<Message> {
-        let message: Message = self.fetch("messages").await?;
-        if &message.channel != channel.id() {
+    pub async fn fetch_message(&self, db: &Database, channel: &Channel) -> Result<Message> {
+        let message = db.get_message_by_id(&self.id).await?;
+        if message.channel != channel.id() {
             Err(Error::InvalidOperation)
         } else {
             Ok(message)
@@ -76,7 +76,7 @@ impl<'a> PermissionCalculator<'a> {
                 }
                 Err(error) => {
                     match &error {
-                        Error::NotFound => Ok(0),
+                        Error::UnknownMember => Ok(0),
                         _ => Err(error)
                     }
                 }
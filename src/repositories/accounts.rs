@@ -0,0 +1,23 @@
+use crate::entities::Subscription;
+use crate::util::result::Result;
+use enum_dispatch::enum_dispatch;
+use rocket::async_trait;
+use web_push::SubscriptionInfo;
+
+#[async_trait]
+#[enum_dispatch]
+pub trait AccountRepository {
+    async fn get_accounts_subscriptions(
+        &self,
+        target_ids: Vec<&str>,
+    ) -> Result<Vec<SubscriptionInfo>>;
+    async fn subscribe(
+        &self,
+        account_id: &str,
+        session_id: &str,
+        subscription: Subscription,
+    ) -> Result<()>;
+    async fn unsubscribe(&self, account_id: &str, session_id: &str) -> Result<()>;
+    /// Remove the push subscriptions of every session on an account.
+    async fn delete_subscriptions(&self, account_id: &str) -> Result<()>;
+}
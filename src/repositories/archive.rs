@@ -0,0 +1,20 @@
+use crate::entities::{ArchiveEntity, ArchivePage, EntityKind};
+use crate::util::result::Result;
+use enum_dispatch::enum_dispatch;
+use mongodb::bson::Bson;
+use rocket::async_trait;
+
+#[async_trait]
+#[enum_dispatch]
+pub trait ArchiveRepository {
+    /// Up to `limit` entities of `kind` in key order, starting after the
+    /// `last` key of the previous page.
+    async fn get_entities_page(
+        &self,
+        kind: EntityKind,
+        after: Option<Bson>,
+        limit: i64,
+    ) -> Result<ArchivePage>;
+    /// Write entities, replacing any with the same key.
+    async fn restore_entities(&self, entities: Vec<ArchiveEntity>) -> Result<()>;
+}
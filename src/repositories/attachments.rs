@@ -0,0 +1,19 @@
+use crate::entities::File;
+use crate::util::result::Result;
+use enum_dispatch::enum_dispatch;
+use rocket::async_trait;
+
+#[async_trait]
+#[enum_dispatch]
+pub trait AttachmentRepository {
+    async fn get_attachment(&self, id: &str, tag: &str, parent_type: &str) -> Result<File>;
+    async fn link_attachment_to_parent(
+        &self,
+        id: &str,
+        parent_type: &str,
+        parent_id: &str,
+    ) -> Result<()>;
+    async fn delete_attachment(&self, id: &str) -> Result<()>;
+    async fn delete_attachments(&self, ids: Vec<&str>) -> Result<()>;
+    async fn delete_attachments_of_messages(&self, message_ids: Vec<&str>) -> Result<()>;
+}
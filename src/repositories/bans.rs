@@ -0,0 +1,19 @@
+use crate::entities::Ban;
+use crate::util::result::Result;
+use enum_dispatch::enum_dispatch;
+use rocket::async_trait;
+
+#[async_trait]
+#[enum_dispatch]
+pub trait BanRepository {
+    async fn delete_server_ban(&self, server_id: &str, user_id: &str) -> Result<()>;
+    async fn is_user_banned(&self, server_id: &str, user_id: &str) -> Result<bool>;
+    async fn get_ban(&self, server_id: &str, user_id: &str) -> Result<Ban>;
+    async fn get_bans(&self, server_id: &str) -> Result<Vec<Ban>>;
+    async fn add_server_ban(
+        &self,
+        server_id: &str,
+        user_id: &str,
+        reason: Option<&str>,
+    ) -> Result<()>;
+}
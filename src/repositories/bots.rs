@@ -0,0 +1,16 @@
+use crate::entities::Bot;
+use crate::util::result::Result;
+use enum_dispatch::enum_dispatch;
+use mongodb::bson::Document;
+use rocket::async_trait;
+
+#[async_trait]
+#[enum_dispatch]
+pub trait BotRepository {
+    async fn get_bot_by_id(&self, id: &str) -> Result<Bot>;
+    async fn get_bot_count_owned_by_user(&self, user_id: &str) -> Result<u64>;
+    async fn get_bots_owned_by_user_id(&self, id: &str) -> Result<Vec<Bot>>;
+    async fn add_bot(&self, bot: &Bot) -> Result<()>;
+    async fn delete_bot(&self, id: &str) -> Result<()>;
+    async fn apply_bot_changes(&self, id: &str, change_doc: Document) -> Result<()>;
+}
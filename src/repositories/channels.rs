@@ -0,0 +1,59 @@
+use crate::entities::Channel;
+use crate::util::result::Result;
+use enum_dispatch::enum_dispatch;
+use mongodb::bson::Document;
+use rocket::async_trait;
+
+#[async_trait]
+#[enum_dispatch]
+pub trait ChannelRepository {
+    async fn get_channel_by_id(&self, id: &str) -> Result<Channel>;
+    async fn does_channel_exist_by_nonce(&self, nonce: &str) -> Result<bool>;
+    async fn remove_recipient_from_channel(
+        &self,
+        channel_id: &str,
+        recipient_id: &str,
+    ) -> Result<()>;
+    async fn update_channel_role_permissions(
+        &self,
+        channel_id: &str,
+        role: &str,
+        permissions: i32,
+    ) -> Result<()>;
+    async fn update_channel_permissions(&self, channel_id: &str, permissions: i32) -> Result<()>;
+    async fn update_channel_default_permissions(
+        &self,
+        channel_id: &str,
+        default_permissions: i32,
+    ) -> Result<()>;
+    async fn delete_server_channels_role_permissions(
+        &self,
+        server_id: &str,
+        role_id: &str,
+    ) -> Result<()>;
+    async fn get_dm_channels_from_user(&self, user_id: &str) -> Result<Vec<Document>>;
+    async fn get_dm_channel(&self, user_a: &str, user_b: &str) -> Result<Option<Document>>;
+    async fn delete_all_channels_from_server(&self, server_id: &str) -> Result<()>;
+    async fn add_channel(&self, channel: &Channel) -> Result<()>;
+    async fn delete_channel(&self, id: &str) -> Result<()>;
+    async fn add_recipient_to_channel(&self, channel_id: &str, recipient_id: &str) -> Result<()>;
+    async fn are_users_connected_in_dms_or_group(&self, user_a: &str, user_b: &str)
+        -> Result<bool>;
+    async fn get_sms_dms_groups_where_user_is_recipient(
+        &self,
+        channel_ids: Vec<&str>,
+        user_id: &str,
+    ) -> Result<Vec<Channel>>;
+    async fn get_channel_ids_from_sms_dms_groups_where_user_is_recipient(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<String>>;
+    async fn make_channel_inactive(&self, channel_id: &str) -> Result<()>;
+    async fn update_channel_owner(
+        &self,
+        channel_id: &str,
+        new_owner: &str,
+        old_owner: &str,
+    ) -> Result<()>;
+    async fn apply_channel_changes(&self, channel_id: &str, change_doc: Document) -> Result<()>;
+}
@@ -0,0 +1,14 @@
+use crate::entities::Invite;
+use crate::util::result::Result;
+use enum_dispatch::enum_dispatch;
+use rocket::async_trait;
+
+#[async_trait]
+#[enum_dispatch]
+pub trait InviteRepository {
+    async fn delete_invites_associated_to_channel(&self, id: &str) -> Result<()>;
+    async fn get_invite_by_id(&self, id: &str) -> Result<Invite>;
+    async fn add_invite(&self, invite: &Invite) -> Result<()>;
+    async fn delete_invite(&self, id: &str) -> Result<()>;
+    async fn get_invites_of_server(&self, server_id: &str) -> Result<Vec<Invite>>;
+}
@@ -0,0 +1,24 @@
+use crate::entities::Job;
+use crate::util::result::Result;
+use chrono::Duration;
+use enum_dispatch::enum_dispatch;
+use rocket::async_trait;
+
+#[async_trait]
+#[enum_dispatch]
+pub trait JobRepository {
+    async fn get_job(&self, id: &str) -> Result<Job>;
+    /// Every job that has not finished yet.
+    async fn get_jobs(&self) -> Result<Vec<Job>>;
+    async fn add_job(&self, job: &Job) -> Result<()>;
+    /// Claim a job for `owner` until `lease` from now, if nobody else holds
+    /// an unexpired claim on it. Also renews a claim `owner` already holds.
+    /// Returns whether the claim succeeded.
+    async fn claim_job(&self, id: &str, owner: &str, lease: Duration) -> Result<bool>;
+    /// Give up `owner`'s claim on a job, so another process can take it.
+    async fn release_job(&self, id: &str, owner: &str) -> Result<()>;
+    /// Record progress on a job, returning `false` if `owner` no longer
+    /// holds it.
+    async fn set_job_step(&self, id: &str, owner: &str, step: i32) -> Result<bool>;
+    async fn delete_job(&self, id: &str) -> Result<()>;
+}
@@ -0,0 +1,33 @@
+use crate::entities::Member;
+use crate::util::result::Result;
+use enum_dispatch::enum_dispatch;
+use mongodb::bson::Document;
+use rocket::async_trait;
+
+#[async_trait]
+#[enum_dispatch]
+pub trait MemberRepository {
+    async fn get_server_member(&self, server_id: &str, user_id: &str) -> Result<Member>;
+    async fn get_server_members(&self, server_id: &str) -> Result<Vec<Member>>;
+    async fn add_server_member(&self, server_id: &str, user_id: &str) -> Result<()>;
+    async fn delete_server_member(&self, server_id: &str, user_id: &str) -> Result<i64>;
+    async fn get_server_member_count(&self, server_id: &str) -> Result<i64>;
+    async fn get_users_memberships(&self, user_id: &str) -> Result<Vec<Member>>;
+    async fn is_user_member_in_one_of_servers(
+        &self,
+        user_id: &str,
+        server_ids: Vec<&str>,
+    ) -> Result<bool>;
+    async fn apply_server_member_changes(
+        &self,
+        server_id: &str,
+        user_id: &str,
+        change_doc: Document,
+    ) -> Result<()>;
+    async fn delete_role_from_server_members(&self, server_id: &str, role_id: &str) -> Result<()>;
+    async fn get_server_memberships_by_ids(
+        &self,
+        user_id: &str,
+        server_ids: Vec<&str>,
+    ) -> Result<Vec<Member>>;
+}
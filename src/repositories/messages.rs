@@ -0,0 +1,55 @@
+use crate::entities::microservice::january::Embed;
+use crate::entities::{Message, MessageFilter, MessagePage, Sort};
+use crate::util::result::Result;
+use chrono::Duration;
+use enum_dispatch::enum_dispatch;
+use mongodb::bson::Document;
+use rocket::async_trait;
+
+#[async_trait]
+#[enum_dispatch]
+pub trait MessageRepository {
+    async fn get_message_by_id(&self, id: &str) -> Result<Message>;
+    async fn set_message_updates(&self, message_id: &str, set_doc: Document) -> Result<()>;
+    async fn get_ids_from_messages_with_attachments(&self, channel_id: &str)
+        -> Result<Vec<String>>;
+    async fn delete_messages_from_channel(&self, channel_id: &str) -> Result<()>;
+    async fn add_message(&self, message: &Message) -> Result<()>;
+    async fn add_embeds_to_message(&self, message_id: &str, embeds: &Vec<Embed>) -> Result<()>;
+    async fn delete_message(&self, message_id: &str) -> Result<()>;
+    async fn get_messages_by_ids_and_channel(
+        &self,
+        message_ids: Vec<&str>,
+        channel_id: &str,
+    ) -> Result<Vec<Message>>;
+    async fn search_messages(
+        &self,
+        channel_id: &str,
+        search: &str,
+        options_before: Option<&str>,
+        options_after: Option<&str>,
+        limit: i64,
+        sort: Sort,
+    ) -> Result<Vec<Message>>;
+    async fn does_message_exist_by_nonce(&self, nonce: &str) -> Result<bool>;
+    async fn delete_messages_bulk(
+        &self,
+        channel_id: &str,
+        filter: &MessageFilter,
+    ) -> Result<Vec<String>>;
+    async fn soft_delete_message(&self, message_id: &str, deleted_by: &str) -> Result<()>;
+    async fn restore_message(&self, message_id: &str, grace_period: Duration) -> Result<()>;
+    async fn get_soft_deleted_messages(&self, channel_id: &str) -> Result<Vec<Message>>;
+    async fn purge_soft_deleted_messages(&self, grace_period: Duration) -> Result<Vec<String>>;
+    /// Up to `limit` messages sent by `author` in `_id` order, starting after
+    /// the `last` ID of the previous page. Soft-deleted messages are included.
+    async fn get_messages_by_author(
+        &self,
+        author: &str,
+        after: Option<&str>,
+        limit: i64,
+    ) -> Result<MessagePage>;
+    /// Blank the content of messages and drop their attachments and embeds,
+    /// keeping the messages themselves so replies still resolve.
+    async fn scrub_messages(&self, message_ids: Vec<&str>) -> Result<()>;
+}
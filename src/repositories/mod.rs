@@ -0,0 +1,67 @@
+mod accounts;
+mod archive;
+mod attachments;
+mod bans;
+mod bots;
+mod channels;
+mod invites;
+mod jobs;
+mod members;
+mod messages;
+mod servers;
+mod settings;
+mod unreads;
+mod users;
+
+pub use accounts::AccountRepository;
+pub use archive::ArchiveRepository;
+pub use attachments::AttachmentRepository;
+pub use bans::BanRepository;
+pub use bots::BotRepository;
+pub use channels::ChannelRepository;
+pub use invites::InviteRepository;
+pub use jobs::JobRepository;
+pub use members::MemberRepository;
+pub use messages::MessageRepository;
+pub use servers::ServerRepository;
+pub use settings::SettingsRepository;
+pub use unreads::UnreadRepository;
+pub use users::UserRepository;
+
+/// Everything a driver offers. Consumers that only need part of the
+/// database can depend on (and mock) the individual repositories instead.
+pub trait Queries:
+    UserRepository
+    + AccountRepository
+    + AttachmentRepository
+    + BotRepository
+    + InviteRepository
+    + UnreadRepository
+    + ChannelRepository
+    + MessageRepository
+    + BanRepository
+    + MemberRepository
+    + ServerRepository
+    + SettingsRepository
+    + ArchiveRepository
+    + JobRepository
+{
+}
+
+impl<T> Queries for T where
+    T: UserRepository
+        + AccountRepository
+        + AttachmentRepository
+        + BotRepository
+        + InviteRepository
+        + UnreadRepository
+        + ChannelRepository
+        + MessageRepository
+        + BanRepository
+        + MemberRepository
+        + ServerRepository
+        + SettingsRepository
+        + ArchiveRepository
+        + JobRepository
+{
+}
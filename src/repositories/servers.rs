@@ -0,0 +1,41 @@
+use crate::entities::Server;
+use crate::util::result::Result;
+use enum_dispatch::enum_dispatch;
+use mongodb::bson::Document;
+use rocket::async_trait;
+
+#[async_trait]
+#[enum_dispatch]
+pub trait ServerRepository {
+    async fn get_server_by_id(&self, id: &str) -> Result<Server>;
+    async fn update_server_permissions(
+        &self,
+        server_id: &str,
+        role_id: &str,
+        server_permissions: i32,
+        channel_permissions: i32,
+    ) -> Result<()>;
+    async fn update_server_default_permissions(
+        &self,
+        server_id: &str,
+        server_permissions: i32,
+        channel_permissions: i32,
+    ) -> Result<()>;
+    async fn apply_server_changes(&self, server_id: &str, change_doc: Document) -> Result<()>;
+    async fn add_server(&self, server: &Server) -> Result<()>;
+    async fn get_servers(&self, server_ids: Vec<&str>) -> Result<Vec<Server>>;
+    async fn add_channel_to_server(&self, server_id: &str, channel_id: &str) -> Result<()>;
+    async fn create_role(
+        &self,
+        server_id: &str,
+        role_id: &str,
+        role_name: &str,
+        default_permission: i32,
+        default_permission_server: i32,
+    ) -> Result<()>;
+    async fn delete_role(&self, server_id: &str, role_id: &str) -> Result<()>;
+    async fn does_server_exist_by_nonce(&self, nonce: &str) -> Result<bool>;
+    /// Delete a server along with its channels, their messages, invites and
+    /// unreads, and its members and bans.
+    async fn delete_server(&self, id: &str) -> Result<()>;
+}
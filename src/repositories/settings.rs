@@ -0,0 +1,21 @@
+use crate::entities::{UserSettingsEntry, UserSettingsSyncResult};
+use crate::util::result::Result;
+use enum_dispatch::enum_dispatch;
+use mongodb::bson::Document;
+use rocket::async_trait;
+
+#[async_trait]
+#[enum_dispatch]
+pub trait SettingsRepository {
+    async fn update_user_settings(&self, user_id: &str, set_doc: Document) -> Result<()>;
+    async fn get_user_settings_doc(
+        &self,
+        user_id: &str,
+        option_keys: Vec<&str>,
+    ) -> Result<Option<Document>>;
+    async fn sync_user_settings(
+        &self,
+        user_id: &str,
+        entries: Vec<UserSettingsEntry>,
+    ) -> Result<UserSettingsSyncResult>;
+}
@@ -0,0 +1,42 @@
+use crate::entities::ChannelUnreadCount;
+use crate::util::result::Result;
+use enum_dispatch::enum_dispatch;
+use mongodb::bson::Document;
+use rocket::async_trait;
+
+#[async_trait]
+#[enum_dispatch]
+pub trait UnreadRepository {
+    async fn delete_channel_unreads(&self, channel_id: &str) -> Result<()>;
+    async fn delete_unreads_for_user(&self, user_id: &str) -> Result<()>;
+    async fn delete_multi_channel_unreads_for_user(
+        &self,
+        channel_ids: Vec<&str>,
+        user_id: &str,
+    ) -> Result<()>;
+    async fn add_mentions_to_channel_unreads(
+        &self,
+        channel_id: &str,
+        mentions: Vec<&str>,
+        message: &str,
+    ) -> Result<()>;
+    async fn add_channels_to_unreads_for_user(
+        &self,
+        channel_ids: Vec<&str>,
+        user_id: &str,
+        current_time: &str,
+    ) -> Result<()>;
+    async fn get_unreads_for_user(&self, user_id: &str) -> Result<Vec<Document>>;
+    async fn update_last_message_in_channel_unreads(
+        &self,
+        channel_id: &str,
+        user_id: &str,
+        message_id: &str,
+    ) -> Result<()>;
+    async fn get_unread_counts_for_user(
+        &self,
+        user_id: &str,
+        channel_ids: Vec<&str>,
+    ) -> Result<Vec<ChannelUnreadCount>>;
+    async fn mark_server_as_read(&self, server_id: &str, user_id: &str) -> Result<()>;
+}
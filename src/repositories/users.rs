@@ -0,0 +1,64 @@
+use crate::entities::{BannedUser, RelationshipStatus, User};
+use crate::util::result::Result;
+use enum_dispatch::enum_dispatch;
+use mongodb::bson::Document;
+use rocket::async_trait;
+
+#[async_trait]
+#[enum_dispatch]
+pub trait UserRepository {
+    async fn get_user_by_id(&self, id: &str) -> Result<User>;
+    async fn get_user_by_username(&self, username: &str) -> Result<User>;
+    async fn get_user_id_by_bot_token(&self, token: &str) -> Result<String>;
+    async fn get_users(&self, user_ids: Vec<&str>) -> Result<Vec<User>>;
+    async fn get_users_as_banned_users(&self, user_ids: Vec<&str>) -> Result<Vec<BannedUser>>;
+    async fn get_bot_users_owned_by_user_id(&self, id: &str) -> Result<Vec<User>>;
+    async fn get_mutual_friends_ids(&self, user_id_a: &str, user_id_b: &str)
+        -> Result<Vec<String>>;
+    async fn add_user(&self, id: &str, username: &str) -> Result<()>;
+    async fn add_bot_user(&self, id: &str, username: &str, owner_id: &str) -> Result<()>;
+    async fn delete_user(&self, id: &str) -> Result<()>;
+    async fn update_username(&self, id: &str, new_username: &str) -> Result<()>;
+    async fn make_user_already_in_relations_blocked(
+        &self,
+        origin_id: &str,
+        target_id: &str,
+    ) -> Result<()>;
+    async fn make_user_already_in_relations_blocked_by(
+        &self,
+        target_id: &str,
+        origin_id: &str,
+    ) -> Result<()>;
+    async fn make_user_not_in_relations_blocked(
+        &self,
+        origin_id: &str,
+        target_id: &str,
+    ) -> Result<()>;
+    async fn make_user_not_in_relations_blocked_by(
+        &self,
+        target_id: &str,
+        origin_id: &str,
+    ) -> Result<()>;
+    async fn apply_profile_changes(&self, id: &str, change_doc: Document) -> Result<()>;
+    async fn remove_user_from_relations(&self, id: &str, target_id: &str) -> Result<()>;
+    async fn send_friend_request(
+        &self,
+        user_id: &str,
+        target_id: &str,
+    ) -> Result<RelationshipStatus>;
+    async fn accept_friend_request(
+        &self,
+        user_id: &str,
+        target_id: &str,
+    ) -> Result<RelationshipStatus>;
+    async fn deny_friend_request(
+        &self,
+        user_id: &str,
+        target_id: &str,
+    ) -> Result<RelationshipStatus>;
+    async fn remove_friend(&self, user_id: &str, target_id: &str) -> Result<RelationshipStatus>;
+    async fn block_user(&self, user_id: &str, target_id: &str) -> Result<RelationshipStatus>;
+    async fn unblock_user(&self, user_id: &str, target_id: &str) -> Result<RelationshipStatus>;
+    /// Clear a user's relations and remove them from everyone else's.
+    async fn delete_all_relations(&self, id: &str) -> Result<()>;
+}
@@ -0,0 +1,358 @@
+use crate::drivers::mongo::{MongoOptions, ReadPolicy, ValidationLevel};
+
+use log::warn;
+use serde::Deserialize;
+use std::env;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Application settings, read from an optional TOML file and then
+/// overridden by `REVOLT_*` environment variables.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Config {
+    // Application Settings
+    pub mongo_uri: String,
+    pub database: DatabaseConfig,
+    pub ws_host: String,
+    pub public_url: String,
+    pub app_url: String,
+    pub external_ws_url: String,
+
+    pub autumn_url: Option<String>,
+    pub january_url: Option<String>,
+    pub voso_url: Option<String>,
+    pub voso_ws_host: String,
+    pub voso_manage_token: Option<String>,
+
+    pub hcaptcha_key: Option<String>,
+    pub hcaptcha_sitekey: String,
+    pub vapid_private_key: String,
+    pub vapid_public_key: String,
+
+    // Application Flags
+    pub invite_only: bool,
+    /// Forces email verification on or off; by default it is on whenever
+    /// SMTP is fully configured.
+    pub use_email_verification: Option<bool>,
+    pub unsafe_no_email: bool,
+    pub unsafe_no_captcha: bool,
+
+    // SMTP Settings
+    pub smtp_host: Option<String>,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub smtp_from: Option<String>,
+
+    // Application Logic Settings
+    pub max_group_size: usize,
+    pub max_bot_count: usize,
+    pub early_adopter_badge: i64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct DatabaseConfig {
+    pub name: String,
+    pub collection_prefix: String,
+    pub read_policy: ReadPolicy,
+    pub auto_migrate: bool,
+    /// Seconds to wait for another process to finish migrating.
+    pub migration_lock_wait: u64,
+    pub validation_level: ValidationLevel,
+}
+
+/// A single thing wrong with the configuration.
+#[derive(Debug, Clone)]
+pub struct ConfigProblem {
+    pub key: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            mongo_uri: String::new(),
+            database: DatabaseConfig::default(),
+            ws_host: "0.0.0.0:9000".to_string(),
+            public_url: String::new(),
+            app_url: String::new(),
+            external_ws_url: String::new(),
+
+            autumn_url: None,
+            january_url: None,
+            voso_url: None,
+            voso_ws_host: "wss://example.com".to_string(),
+            voso_manage_token: None,
+
+            hcaptcha_key: None,
+            hcaptcha_sitekey: "10000000-ffff-ffff-ffff-000000000001".to_string(),
+            vapid_private_key: String::new(),
+            vapid_public_key: String::new(),
+
+            invite_only: false,
+            use_email_verification: None,
+            unsafe_no_email: false,
+            unsafe_no_captcha: false,
+
+            smtp_host: None,
+            smtp_username: None,
+            smtp_password: None,
+            smtp_from: None,
+
+            max_group_size: 50,
+            max_bot_count: 5,
+            early_adopter_badge: 0,
+        }
+    }
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        let options = MongoOptions::default();
+        Self {
+            name: options.database,
+            collection_prefix: options.collection_prefix,
+            read_policy: options.read_policy,
+            auto_migrate: options.auto_migrate,
+            migration_lock_wait: options.migration_lock_wait.as_secs(),
+            validation_level: options.validation_level,
+        }
+    }
+}
+
+impl Config {
+    /// Load the configuration, reporting every problem found rather than
+    /// stopping at the first one.
+    pub fn load(path: Option<&Path>) -> Result<Config, Vec<ConfigProblem>> {
+        let mut config = match path {
+            Some(path) => Config::from_file(path).map_err(|problem| vec![problem])?,
+            None => Config::default(),
+        };
+
+        let mut problems = config.apply_env();
+        problems.append(&mut preflight_checks(&config));
+
+        if problems.is_empty() {
+            Ok(config)
+        } else {
+            Err(problems)
+        }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Config, ConfigProblem> {
+        let source = std::fs::read_to_string(path).map_err(|error| ConfigProblem {
+            key: "config",
+            message: format!("failed to read {}: {}", path.display(), error),
+        })?;
+
+        Config::from_toml(&source)
+    }
+
+    pub fn from_toml(source: &str) -> Result<Config, ConfigProblem> {
+        toml::from_str(source).map_err(|error| ConfigProblem {
+            key: "config",
+            message: error.to_string(),
+        })
+    }
+
+    /// Override settings with any environment variables that are set,
+    /// returning the ones that could not be parsed.
+    pub fn apply_env(&mut self) -> Vec<ConfigProblem> {
+        let mut problems = vec![];
+
+        env_string("REVOLT_MONGO_URI", &mut self.mongo_uri);
+        env_string("REVOLT_DATABASE_NAME", &mut self.database.name);
+        env_string(
+            "REVOLT_COLLECTION_PREFIX",
+            &mut self.database.collection_prefix,
+        );
+        env_parse(
+            "REVOLT_READ_POLICY",
+            &mut self.database.read_policy,
+            &mut problems,
+        );
+        env_flag("REVOLT_AUTO_MIGRATE", &mut self.database.auto_migrate);
+        env_parse(
+            "REVOLT_MIGRATION_LOCK_WAIT",
+            &mut self.database.migration_lock_wait,
+            &mut problems,
+        );
+        env_parse(
+            "REVOLT_VALIDATION_LEVEL",
+            &mut self.database.validation_level,
+            &mut problems,
+        );
+        env_string("REVOLT_WS_HOST", &mut self.ws_host);
+        env_string("REVOLT_PUBLIC_URL", &mut self.public_url);
+        env_string("REVOLT_APP_URL", &mut self.app_url);
+        env_string("REVOLT_EXTERNAL_WS_URL", &mut self.external_ws_url);
+
+        env_option("AUTUMN_PUBLIC_URL", &mut self.autumn_url);
+        env_option("JANUARY_PUBLIC_URL", &mut self.january_url);
+        env_option("VOSO_PUBLIC_URL", &mut self.voso_url);
+        env_string("VOSO_WS_HOST", &mut self.voso_ws_host);
+        env_option("VOSO_MANAGE_TOKEN", &mut self.voso_manage_token);
+
+        env_option("REVOLT_HCAPTCHA_KEY", &mut self.hcaptcha_key);
+        env_string("REVOLT_HCAPTCHA_SITEKEY", &mut self.hcaptcha_sitekey);
+        env_string("REVOLT_VAPID_PRIVATE_KEY", &mut self.vapid_private_key);
+        env_string("REVOLT_VAPID_PUBLIC_KEY", &mut self.vapid_public_key);
+
+        env_flag("REVOLT_INVITE_ONLY", &mut self.invite_only);
+        if let Ok(value) = env::var("REVOLT_USE_EMAIL_VERIFICATION") {
+            self.use_email_verification = Some(value == "1");
+        }
+        env_flag("REVOLT_UNSAFE_NO_EMAIL", &mut self.unsafe_no_email);
+        env_flag("REVOLT_UNSAFE_NO_CAPTCHA", &mut self.unsafe_no_captcha);
+
+        env_option("REVOLT_SMTP_HOST", &mut self.smtp_host);
+        env_option("REVOLT_SMTP_USERNAME", &mut self.smtp_username);
+        env_option("REVOLT_SMTP_PASSWORD", &mut self.smtp_password);
+        env_option("REVOLT_SMTP_FROM", &mut self.smtp_from);
+
+        env_parse(
+            "REVOLT_MAX_GROUP_SIZE",
+            &mut self.max_group_size,
+            &mut problems,
+        );
+        env_parse(
+            "REVOLT_MAX_BOT_COUNT",
+            &mut self.max_bot_count,
+            &mut problems,
+        );
+        env_parse(
+            "REVOLT_EARLY_ADOPTER_BADGE",
+            &mut self.early_adopter_badge,
+            &mut problems,
+        );
+
+        problems
+    }
+
+    pub fn use_email(&self) -> bool {
+        self.use_email_verification.unwrap_or(
+            self.smtp_host.is_some()
+                && self.smtp_username.is_some()
+                && self.smtp_password.is_some()
+                && self.smtp_from.is_some(),
+        )
+    }
+
+    pub fn use_hcaptcha(&self) -> bool {
+        self.hcaptcha_key.is_some()
+    }
+
+    pub fn use_autumn(&self) -> bool {
+        self.autumn_url.is_some()
+    }
+
+    pub fn use_january(&self) -> bool {
+        self.january_url.is_some()
+    }
+
+    pub fn use_voso(&self) -> bool {
+        self.voso_url.is_some() && self.voso_manage_token.is_some()
+    }
+
+    pub fn mongo_options(&self) -> MongoOptions {
+        MongoOptions {
+            read_policy: self.database.read_policy,
+            database: self.database.name.clone(),
+            collection_prefix: self.database.collection_prefix.clone(),
+            auto_migrate: self.database.auto_migrate,
+            migration_lock_wait: Duration::from_secs(self.database.migration_lock_wait),
+            validation_level: self.database.validation_level,
+        }
+    }
+}
+
+/// Check the configuration is usable, returning every problem at once.
+pub fn preflight_checks(config: &Config) -> Vec<ConfigProblem> {
+    let mut problems = vec![];
+    let mut require = |key: &'static str, value: &str| {
+        if value.is_empty() {
+            problems.push(ConfigProblem {
+                key,
+                message: "is required".to_string(),
+            });
+        }
+    };
+
+    require("mongo_uri", &config.mongo_uri);
+    require("database.name", &config.database.name);
+    require("public_url", &config.public_url);
+    require("app_url", &config.app_url);
+    require("external_ws_url", &config.external_ws_url);
+    require("vapid_private_key", &config.vapid_private_key);
+    require("vapid_public_key", &config.vapid_public_key);
+
+    if config.max_group_size == 0 {
+        problems.push(ConfigProblem {
+            key: "max_group_size",
+            message: "must be at least 1".to_string(),
+        });
+    }
+
+    if !config.use_email() {
+        if cfg!(debug_assertions) {
+            warn!("No SMTP settings specified! Remember to configure email.");
+        } else if !config.unsafe_no_email {
+            problems.push(ConfigProblem {
+                key: "smtp_host",
+                message: "running in production without email is not recommended, set REVOLT_UNSAFE_NO_EMAIL=1 to override".to_string(),
+            });
+        }
+    }
+
+    if !config.use_hcaptcha() {
+        if cfg!(debug_assertions) {
+            warn!("No Captcha key specified! Remember to add hCaptcha key.");
+        } else if !config.unsafe_no_captcha {
+            problems.push(ConfigProblem {
+                key: "hcaptcha_key",
+                message: "running in production without CAPTCHA is not recommended, set REVOLT_UNSAFE_NO_CAPTCHA=1 to override".to_string(),
+            });
+        }
+    }
+
+    problems
+}
+
+fn env_string(key: &str, target: &mut String) {
+    if let Ok(value) = env::var(key) {
+        *target = value;
+    }
+}
+
+fn env_option(key: &str, target: &mut Option<String>) {
+    if let Ok(value) = env::var(key) {
+        *target = Some(value);
+    }
+}
+
+fn env_flag(key: &str, target: &mut bool) {
+    if let Ok(value) = env::var(key) {
+        *target = value == "1";
+    }
+}
+
+fn env_parse<T: FromStr>(key: &'static str, target: &mut T, problems: &mut Vec<ConfigProblem>) {
+    if let Ok(value) = env::var(key) {
+        match value.parse() {
+            Ok(value) => *target = value,
+            Err(_) => problems.push(ConfigProblem {
+                key,
+                message: format!("could not parse {:?}", value),
+            }),
+        }
+    }
+}
@@ -1,3 +1,3 @@
+pub mod config;
 pub mod result;
-// pub mod variables;
 // pub mod ratelimit;
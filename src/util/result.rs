@@ -2,7 +2,8 @@ use rocket::http::{ContentType, Status};
 use rocket::request::Request;
 use rocket::response::{self, Responder, Response};
 use serde::Serialize;
-use serde_json::json;
+use serde_json::{json, Value};
+use std::fmt;
 use std::io::Cursor;
 use validator::ValidationErrors;
 
@@ -27,6 +28,7 @@ pub enum Error {
     UnknownChannel,
     UnknownAttachment,
     UnknownMessage,
+    UnknownInvite,
     CannotEditMessage,
     CannotJoinCall,
     TooManyAttachments,
@@ -41,10 +43,13 @@ pub enum Error {
 
     // ? Server related errors.
     UnknownServer,
+    UnknownMember,
+    UnknownBan,
     InvalidRole,
     Banned,
 
     // ? Bot related errors.
+    UnknownBot,
     ReachedMaximumBots,
     IsBot,
     BotIsPrivate,
@@ -57,6 +62,19 @@ pub enum Error {
     DatabaseError {
         operation: &'static str,
         with: &'static str,
+        #[serde(skip)]
+        id: Option<String>,
+        #[serde(skip)]
+        source: Option<Box<dyn std::error::Error + Send + Sync>>,
+    },
+    /// Only logged: clients should not learn collection names or IDs.
+    InvalidDocument {
+        #[serde(skip)]
+        collection: &'static str,
+        #[serde(skip)]
+        id: String,
+        #[serde(skip)]
+        source: Option<Box<dyn std::error::Error + Send + Sync>>,
     },
     InternalError,
     MissingPermission,
@@ -64,8 +82,483 @@ pub enum Error {
     InvalidCredentials,
     DuplicateNonce,
     VosoUnavailable,
-    NotFound,
     NoEffect,
+    DatabaseTooNew {
+        revision: i32,
+        supported: i32,
+    },
+    IrreversibleMigration {
+        revision: i32,
+    },
+    MigrationLocked {
+        owner: String,
+    },
+    ArchiveError {
+        reason: String,
+    },
+    UnknownJob,
+    JobClaimed {
+        owner: String,
+    },
+}
+
+/// Describes one error variant for clients.
+///
+/// Codes are stable: once published, a code keeps its meaning and is never
+/// reused, even if the variant is removed.
+#[derive(Serialize, Debug)]
+pub struct ErrorInfo {
+    /// Variant name, matching the `type` field of serialised errors.
+    pub kind: &'static str,
+    pub code: u16,
+    pub status: u16,
+    pub message: &'static str,
+    /// Extra fields serialised alongside `type` for this variant.
+    pub details: &'static [&'static str],
+}
+
+// One entry per variant. `Error::info` maps every variant to its entry, so
+// the compiler catches a variant without one; each entry must also be listed
+// in `ERROR_CATALOGUE` below.
+const LABEL_ME: ErrorInfo = ErrorInfo {
+    kind: "LabelMe",
+    code: 1000,
+    status: 500,
+    message: "This error has not been labelled yet.",
+    details: &[],
+};
+
+const ALREADY_ONBOARDED: ErrorInfo = ErrorInfo {
+    kind: "AlreadyOnboarded",
+    code: 1100,
+    status: 403,
+    message: "This account has already completed onboarding.",
+    details: &[],
+};
+
+const USERNAME_TAKEN: ErrorInfo = ErrorInfo {
+    kind: "UsernameTaken",
+    code: 2000,
+    status: 409,
+    message: "This username is already taken.",
+    details: &[],
+};
+
+const UNKNOWN_USER: ErrorInfo = ErrorInfo {
+    kind: "UnknownUser",
+    code: 2001,
+    status: 404,
+    message: "The user could not be found.",
+    details: &[],
+};
+
+const ALREADY_FRIENDS: ErrorInfo = ErrorInfo {
+    kind: "AlreadyFriends",
+    code: 2002,
+    status: 409,
+    message: "You are already friends with this user.",
+    details: &[],
+};
+
+const ALREADY_SENT_REQUEST: ErrorInfo = ErrorInfo {
+    kind: "AlreadySentRequest",
+    code: 2003,
+    status: 409,
+    message: "You have already sent this user a friend request.",
+    details: &[],
+};
+
+const BLOCKED: ErrorInfo = ErrorInfo {
+    kind: "Blocked",
+    code: 2004,
+    status: 409,
+    message: "You have blocked this user.",
+    details: &[],
+};
+
+const BLOCKED_BY_OTHER: ErrorInfo = ErrorInfo {
+    kind: "BlockedByOther",
+    code: 2005,
+    status: 403,
+    message: "This user has blocked you.",
+    details: &[],
+};
+
+const NOT_FRIENDS: ErrorInfo = ErrorInfo {
+    kind: "NotFriends",
+    code: 2006,
+    status: 403,
+    message: "You are not friends with this user.",
+    details: &[],
+};
+
+const UNKNOWN_CHANNEL: ErrorInfo = ErrorInfo {
+    kind: "UnknownChannel",
+    code: 3000,
+    status: 404,
+    message: "The channel could not be found.",
+    details: &[],
+};
+
+const UNKNOWN_ATTACHMENT: ErrorInfo = ErrorInfo {
+    kind: "UnknownAttachment",
+    code: 3001,
+    status: 400,
+    message: "The attachment could not be found.",
+    details: &[],
+};
+
+const UNKNOWN_MESSAGE: ErrorInfo = ErrorInfo {
+    kind: "UnknownMessage",
+    code: 3002,
+    status: 404,
+    message: "The message could not be found.",
+    details: &[],
+};
+
+const UNKNOWN_INVITE: ErrorInfo = ErrorInfo {
+    kind: "UnknownInvite",
+    code: 3003,
+    status: 404,
+    message: "The invite could not be found.",
+    details: &[],
+};
+
+const CANNOT_EDIT_MESSAGE: ErrorInfo = ErrorInfo {
+    kind: "CannotEditMessage",
+    code: 3004,
+    status: 403,
+    message: "You cannot edit this message.",
+    details: &[],
+};
+
+const CANNOT_JOIN_CALL: ErrorInfo = ErrorInfo {
+    kind: "CannotJoinCall",
+    code: 3005,
+    status: 400,
+    message: "You cannot join a call in this channel.",
+    details: &[],
+};
+
+const TOO_MANY_ATTACHMENTS: ErrorInfo = ErrorInfo {
+    kind: "TooManyAttachments",
+    code: 3006,
+    status: 400,
+    message: "The message has too many attachments.",
+    details: &[],
+};
+
+const TOO_MANY_REPLIES: ErrorInfo = ErrorInfo {
+    kind: "TooManyReplies",
+    code: 3007,
+    status: 400,
+    message: "The message replies to too many messages.",
+    details: &[],
+};
+
+const EMPTY_MESSAGE: ErrorInfo = ErrorInfo {
+    kind: "EmptyMessage",
+    code: 3008,
+    status: 422,
+    message: "The message is empty.",
+    details: &[],
+};
+
+const CANNOT_REMOVE_YOURSELF: ErrorInfo = ErrorInfo {
+    kind: "CannotRemoveYourself",
+    code: 3009,
+    status: 400,
+    message: "You cannot remove yourself from this group.",
+    details: &[],
+};
+
+const GROUP_TOO_LARGE: ErrorInfo = ErrorInfo {
+    kind: "GroupTooLarge",
+    code: 3010,
+    status: 403,
+    message: "The group has reached its maximum size.",
+    details: &["max"],
+};
+
+const ALREADY_IN_GROUP: ErrorInfo = ErrorInfo {
+    kind: "AlreadyInGroup",
+    code: 3011,
+    status: 409,
+    message: "The user is already in this group.",
+    details: &[],
+};
+
+const NOT_IN_GROUP: ErrorInfo = ErrorInfo {
+    kind: "NotInGroup",
+    code: 3012,
+    status: 404,
+    message: "The user is not in this group.",
+    details: &[],
+};
+
+const UNKNOWN_SERVER: ErrorInfo = ErrorInfo {
+    kind: "UnknownServer",
+    code: 4000,
+    status: 404,
+    message: "The server could not be found.",
+    details: &[],
+};
+
+const UNKNOWN_MEMBER: ErrorInfo = ErrorInfo {
+    kind: "UnknownMember",
+    code: 4001,
+    status: 404,
+    message: "The server member could not be found.",
+    details: &[],
+};
+
+const UNKNOWN_BAN: ErrorInfo = ErrorInfo {
+    kind: "UnknownBan",
+    code: 4002,
+    status: 404,
+    message: "The ban could not be found.",
+    details: &[],
+};
+
+const INVALID_ROLE: ErrorInfo = ErrorInfo {
+    kind: "InvalidRole",
+    code: 4003,
+    status: 404,
+    message: "The role does not exist.",
+    details: &[],
+};
+
+const BANNED: ErrorInfo = ErrorInfo {
+    kind: "Banned",
+    code: 4004,
+    status: 403,
+    message: "You are banned from this server.",
+    details: &[],
+};
+
+const UNKNOWN_BOT: ErrorInfo = ErrorInfo {
+    kind: "UnknownBot",
+    code: 5000,
+    status: 404,
+    message: "The bot could not be found.",
+    details: &[],
+};
+
+const REACHED_MAXIMUM_BOTS: ErrorInfo = ErrorInfo {
+    kind: "ReachedMaximumBots",
+    code: 5001,
+    status: 400,
+    message: "You have reached the maximum number of bots.",
+    details: &[],
+};
+
+const IS_BOT: ErrorInfo = ErrorInfo {
+    kind: "IsBot",
+    code: 5002,
+    status: 400,
+    message: "This action is not available to bots.",
+    details: &[],
+};
+
+const BOT_IS_PRIVATE: ErrorInfo = ErrorInfo {
+    kind: "BotIsPrivate",
+    code: 5003,
+    status: 403,
+    message: "This bot is private.",
+    details: &[],
+};
+
+const TOO_MANY_IDS: ErrorInfo = ErrorInfo {
+    kind: "TooManyIds",
+    code: 9000,
+    status: 400,
+    message: "Too many IDs were requested at once.",
+    details: &[],
+};
+
+const FAILED_VALIDATION: ErrorInfo = ErrorInfo {
+    kind: "FailedValidation",
+    code: 9001,
+    status: 422,
+    message: "The request failed validation.",
+    details: &["error"],
+};
+
+const DATABASE_ERROR: ErrorInfo = ErrorInfo {
+    kind: "DatabaseError",
+    code: 9002,
+    status: 500,
+    message: "A database operation failed.",
+    details: &["operation", "with"],
+};
+
+const INVALID_DOCUMENT: ErrorInfo = ErrorInfo {
+    kind: "InvalidDocument",
+    code: 9003,
+    status: 500,
+    message: "A stored document could not be read.",
+    details: &[],
+};
+
+const INTERNAL_ERROR: ErrorInfo = ErrorInfo {
+    kind: "InternalError",
+    code: 9004,
+    status: 500,
+    message: "An internal error occurred.",
+    details: &[],
+};
+
+const MISSING_PERMISSION: ErrorInfo = ErrorInfo {
+    kind: "MissingPermission",
+    code: 9005,
+    status: 403,
+    message: "You do not have permission to do this.",
+    details: &[],
+};
+
+const INVALID_OPERATION: ErrorInfo = ErrorInfo {
+    kind: "InvalidOperation",
+    code: 9006,
+    status: 400,
+    message: "This operation is not valid.",
+    details: &[],
+};
+
+const INVALID_CREDENTIALS: ErrorInfo = ErrorInfo {
+    kind: "InvalidCredentials",
+    code: 9007,
+    status: 403,
+    message: "The credentials are invalid.",
+    details: &[],
+};
+
+const DUPLICATE_NONCE: ErrorInfo = ErrorInfo {
+    kind: "DuplicateNonce",
+    code: 9008,
+    status: 409,
+    message: "A request with this nonce was already processed.",
+    details: &[],
+};
+
+const VOSO_UNAVAILABLE: ErrorInfo = ErrorInfo {
+    kind: "VosoUnavailable",
+    code: 9009,
+    status: 400,
+    message: "The voice server is unavailable.",
+    details: &[],
+};
+
+const NO_EFFECT: ErrorInfo = ErrorInfo {
+    kind: "NoEffect",
+    code: 9010,
+    status: 200,
+    message: "The request had no effect.",
+    details: &[],
+};
+
+const DATABASE_TOO_NEW: ErrorInfo = ErrorInfo {
+    kind: "DatabaseTooNew",
+    code: 9011,
+    status: 500,
+    message: "The database has migrations this version does not know about.",
+    details: &["revision", "supported"],
+};
+
+const IRREVERSIBLE_MIGRATION: ErrorInfo = ErrorInfo {
+    kind: "IrreversibleMigration",
+    code: 9012,
+    status: 500,
+    message: "This migration cannot be reverted.",
+    details: &["revision"],
+};
+
+const MIGRATION_LOCKED: ErrorInfo = ErrorInfo {
+    kind: "MigrationLocked",
+    code: 9013,
+    status: 503,
+    message: "Another process is migrating the database.",
+    details: &["owner"],
+};
+
+const ARCHIVE_ERROR: ErrorInfo = ErrorInfo {
+    kind: "ArchiveError",
+    code: 9014,
+    status: 500,
+    message: "An archive could not be read or written.",
+    details: &["reason"],
+};
+
+const UNKNOWN_JOB: ErrorInfo = ErrorInfo {
+    kind: "UnknownJob",
+    code: 9015,
+    status: 404,
+    message: "The job could not be found.",
+    details: &[],
+};
+
+const JOB_CLAIMED: ErrorInfo = ErrorInfo {
+    kind: "JobClaimed",
+    code: 9016,
+    status: 409,
+    message: "Another process is running this job.",
+    details: &["owner"],
+};
+
+/// Every error the API can return.
+pub static ERROR_CATALOGUE: &[ErrorInfo] = &[
+    LABEL_ME,
+    ALREADY_ONBOARDED,
+    USERNAME_TAKEN,
+    UNKNOWN_USER,
+    ALREADY_FRIENDS,
+    ALREADY_SENT_REQUEST,
+    BLOCKED,
+    BLOCKED_BY_OTHER,
+    NOT_FRIENDS,
+    UNKNOWN_CHANNEL,
+    UNKNOWN_ATTACHMENT,
+    UNKNOWN_MESSAGE,
+    UNKNOWN_INVITE,
+    CANNOT_EDIT_MESSAGE,
+    CANNOT_JOIN_CALL,
+    TOO_MANY_ATTACHMENTS,
+    TOO_MANY_REPLIES,
+    EMPTY_MESSAGE,
+    CANNOT_REMOVE_YOURSELF,
+    GROUP_TOO_LARGE,
+    ALREADY_IN_GROUP,
+    NOT_IN_GROUP,
+    UNKNOWN_SERVER,
+    UNKNOWN_MEMBER,
+    UNKNOWN_BAN,
+    INVALID_ROLE,
+    BANNED,
+    UNKNOWN_BOT,
+    REACHED_MAXIMUM_BOTS,
+    IS_BOT,
+    BOT_IS_PRIVATE,
+    TOO_MANY_IDS,
+    FAILED_VALIDATION,
+    DATABASE_ERROR,
+    INVALID_DOCUMENT,
+    INTERNAL_ERROR,
+    MISSING_PERMISSION,
+    INVALID_OPERATION,
+    INVALID_CREDENTIALS,
+    DUPLICATE_NONCE,
+    VOSO_UNAVAILABLE,
+    NO_EFFECT,
+    DATABASE_TOO_NEW,
+    IRREVERSIBLE_MIGRATION,
+    MIGRATION_LOCKED,
+    ARCHIVE_ERROR,
+    UNKNOWN_JOB,
+    JOB_CLAIMED,
+];
+
+/// Every error the API can return, as JSON, for generating client bindings.
+pub fn error_catalogue() -> Value {
+    json!(ERROR_CATALOGUE)
 }
 
 pub struct EmptyResponse;
@@ -79,58 +572,126 @@ impl<'r> Responder<'r, 'static> for EmptyResponse {
     }
 }
 
+impl Error {
+    /// Wrap a failed database call on the `with` collection, keeping the
+    /// driver's error as the source.
+    pub fn database<E>(operation: &'static str, with: &'static str, source: E) -> Error
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        Error::DatabaseError {
+            operation,
+            with,
+            id: None,
+            source: Some(Box::new(source)),
+        }
+    }
+
+    /// Record which entity a database error concerns.
+    pub fn for_id(self, entity: &str) -> Error {
+        match self {
+            Error::DatabaseError {
+                operation,
+                with,
+                source,
+                ..
+            } => Error::DatabaseError {
+                operation,
+                with,
+                id: Some(entity.to_string()),
+                source,
+            },
+            error => error,
+        }
+    }
+
+    /// Name of the variant, as serialised in the `type` field.
+    pub fn kind(&self) -> &'static str {
+        self.info().kind
+    }
+
+    /// Catalogue entry describing this error.
+    pub fn info(&self) -> &'static ErrorInfo {
+        match self {
+            Error::LabelMe => &LABEL_ME,
+            Error::AlreadyOnboarded => &ALREADY_ONBOARDED,
+            Error::UsernameTaken => &USERNAME_TAKEN,
+            Error::UnknownUser => &UNKNOWN_USER,
+            Error::AlreadyFriends => &ALREADY_FRIENDS,
+            Error::AlreadySentRequest => &ALREADY_SENT_REQUEST,
+            Error::Blocked => &BLOCKED,
+            Error::BlockedByOther => &BLOCKED_BY_OTHER,
+            Error::NotFriends => &NOT_FRIENDS,
+            Error::UnknownChannel => &UNKNOWN_CHANNEL,
+            Error::UnknownAttachment => &UNKNOWN_ATTACHMENT,
+            Error::UnknownMessage => &UNKNOWN_MESSAGE,
+            Error::UnknownInvite => &UNKNOWN_INVITE,
+            Error::CannotEditMessage => &CANNOT_EDIT_MESSAGE,
+            Error::CannotJoinCall => &CANNOT_JOIN_CALL,
+            Error::TooManyAttachments => &TOO_MANY_ATTACHMENTS,
+            Error::TooManyReplies => &TOO_MANY_REPLIES,
+            Error::EmptyMessage => &EMPTY_MESSAGE,
+            Error::CannotRemoveYourself => &CANNOT_REMOVE_YOURSELF,
+            Error::GroupTooLarge { .. } => &GROUP_TOO_LARGE,
+            Error::AlreadyInGroup => &ALREADY_IN_GROUP,
+            Error::NotInGroup => &NOT_IN_GROUP,
+            Error::UnknownServer => &UNKNOWN_SERVER,
+            Error::UnknownMember => &UNKNOWN_MEMBER,
+            Error::UnknownBan => &UNKNOWN_BAN,
+            Error::InvalidRole => &INVALID_ROLE,
+            Error::Banned => &BANNED,
+            Error::UnknownBot => &UNKNOWN_BOT,
+            Error::ReachedMaximumBots => &REACHED_MAXIMUM_BOTS,
+            Error::IsBot => &IS_BOT,
+            Error::BotIsPrivate => &BOT_IS_PRIVATE,
+            Error::TooManyIds => &TOO_MANY_IDS,
+            Error::FailedValidation { .. } => &FAILED_VALIDATION,
+            Error::DatabaseError { .. } => &DATABASE_ERROR,
+            Error::InvalidDocument { .. } => &INVALID_DOCUMENT,
+            Error::InternalError => &INTERNAL_ERROR,
+            Error::MissingPermission => &MISSING_PERMISSION,
+            Error::InvalidOperation => &INVALID_OPERATION,
+            Error::InvalidCredentials => &INVALID_CREDENTIALS,
+            Error::DuplicateNonce => &DUPLICATE_NONCE,
+            Error::VosoUnavailable => &VOSO_UNAVAILABLE,
+            Error::NoEffect => &NO_EFFECT,
+            Error::DatabaseTooNew { .. } => &DATABASE_TOO_NEW,
+            Error::IrreversibleMigration { .. } => &IRREVERSIBLE_MIGRATION,
+            Error::MigrationLocked { .. } => &MIGRATION_LOCKED,
+            Error::ArchiveError { .. } => &ARCHIVE_ERROR,
+            Error::UnknownJob => &UNKNOWN_JOB,
+            Error::JobClaimed { .. } => &JOB_CLAIMED,
+        }
+    }
+
+    /// Stable numeric code identifying this error.
+    pub fn code(&self) -> u16 {
+        self.info().code
+    }
+
+    /// Human-readable English description of this error.
+    pub fn message(&self) -> &'static str {
+        self.info().message
+    }
+
+    /// HTTP status code this error is reported with.
+    pub fn status(&self) -> Status {
+        Status {
+            code: self.info().status,
+        }
+    }
+}
+
 /// HTTP response builder for Error enum
 impl<'r> Responder<'r, 'static> for Error {
     fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
-        let status = match self {
-            Error::LabelMe => Status::InternalServerError,
-
-            Error::AlreadyOnboarded => Status::Forbidden,
-
-            Error::UnknownUser => Status::NotFound,
-            Error::UsernameTaken => Status::Conflict,
-            Error::AlreadyFriends => Status::Conflict,
-            Error::AlreadySentRequest => Status::Conflict,
-            Error::Blocked => Status::Conflict,
-            Error::BlockedByOther => Status::Forbidden,
-            Error::NotFriends => Status::Forbidden,
-
-            Error::UnknownChannel => Status::NotFound,
-            Error::UnknownMessage => Status::NotFound,
-            Error::UnknownAttachment => Status::BadRequest,
-            Error::CannotEditMessage => Status::Forbidden,
-            Error::CannotJoinCall => Status::BadRequest,
-            Error::TooManyAttachments => Status::BadRequest,
-            Error::TooManyReplies => Status::BadRequest,
-            Error::EmptyMessage => Status::UnprocessableEntity,
-            Error::CannotRemoveYourself => Status::BadRequest,
-            Error::GroupTooLarge { .. } => Status::Forbidden,
-            Error::AlreadyInGroup => Status::Conflict,
-            Error::NotInGroup => Status::NotFound,
-
-            Error::UnknownServer => Status::NotFound,
-            Error::InvalidRole => Status::NotFound,
-            Error::Banned => Status::Forbidden,
-
-            Error::ReachedMaximumBots => Status::BadRequest,
-            Error::IsBot => Status::BadRequest,
-            Error::BotIsPrivate => Status::Forbidden,
-
-            Error::FailedValidation { .. } => Status::UnprocessableEntity,
-            Error::DatabaseError { .. } => Status::InternalServerError,
-            Error::InternalError => Status::InternalServerError,
-            Error::MissingPermission => Status::Forbidden,
-            Error::InvalidOperation => Status::BadRequest,
-            Error::TooManyIds => Status::BadRequest,
-            Error::InvalidCredentials => Status::Forbidden,
-            Error::DuplicateNonce => Status::Conflict,
-            Error::VosoUnavailable => Status::BadRequest,
-            Error::NotFound => Status::NotFound,
-            Error::NoEffect => Status::Ok,
-        };
+        let status = self.status();
 
         // Serialize the error data structure into JSON.
-        let string = json!(self).to_string();
+        let mut value = json!(self);
+        value["code"] = json!(self.code());
+        value["message"] = json!(self.message());
+        let string = value.to_string();
 
         // Build and send the request.
         Response::build()
@@ -140,3 +701,232 @@ impl<'r> Responder<'r, 'static> for Error {
             .ok()
     }
 }
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::DatabaseError {
+                operation,
+                with,
+                id,
+                source,
+            } => {
+                write!(f, "database operation {} on {} failed", operation, with)?;
+                if let Some(id) = id {
+                    write!(f, " for {}", id)?;
+                }
+                if let Some(source) = source {
+                    write!(f, ": {}", source)?;
+                }
+                Ok(())
+            }
+            Error::InvalidDocument {
+                collection,
+                id,
+                source,
+            } => {
+                write!(f, "document {} in {} could not be read", id, collection)?;
+                if let Some(source) = source {
+                    write!(f, ": {}", source)?;
+                }
+                Ok(())
+            }
+            Error::DatabaseTooNew {
+                revision,
+                supported,
+            } => write!(
+                f,
+                "database is at revision {} but this version only supports up to {}",
+                revision, supported
+            ),
+            Error::ArchiveError { reason } => write!(f, "archive error: {}", reason),
+            error => f.write_str(error.message()),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::DatabaseError {
+                source: Some(source),
+                ..
+            }
+            | Error::InvalidDocument {
+                source: Some(source),
+                ..
+            } => Some(&**source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, ERROR_CATALOGUE};
+    use serde_json::json;
+    use std::collections::HashSet;
+    use validator::ValidationErrors;
+
+    /// One value of every variant.
+    fn every_error() -> Vec<Error> {
+        vec![
+            Error::LabelMe,
+            Error::AlreadyOnboarded,
+            Error::UsernameTaken,
+            Error::UnknownUser,
+            Error::AlreadyFriends,
+            Error::AlreadySentRequest,
+            Error::Blocked,
+            Error::BlockedByOther,
+            Error::NotFriends,
+            Error::UnknownChannel,
+            Error::UnknownAttachment,
+            Error::UnknownMessage,
+            Error::UnknownInvite,
+            Error::CannotEditMessage,
+            Error::CannotJoinCall,
+            Error::TooManyAttachments,
+            Error::TooManyReplies,
+            Error::EmptyMessage,
+            Error::CannotRemoveYourself,
+            Error::GroupTooLarge { max: 0 },
+            Error::AlreadyInGroup,
+            Error::NotInGroup,
+            Error::UnknownServer,
+            Error::UnknownMember,
+            Error::UnknownBan,
+            Error::InvalidRole,
+            Error::Banned,
+            Error::UnknownBot,
+            Error::ReachedMaximumBots,
+            Error::IsBot,
+            Error::BotIsPrivate,
+            Error::TooManyIds,
+            Error::FailedValidation {
+                error: ValidationErrors::new(),
+            },
+            Error::DatabaseError {
+                operation: "find",
+                with: "users",
+                id: None,
+                source: None,
+            },
+            Error::InvalidDocument {
+                collection: "users",
+                id: String::new(),
+                source: None,
+            },
+            Error::InternalError,
+            Error::MissingPermission,
+            Error::InvalidOperation,
+            Error::InvalidCredentials,
+            Error::DuplicateNonce,
+            Error::VosoUnavailable,
+            Error::NoEffect,
+            Error::DatabaseTooNew {
+                revision: 0,
+                supported: 0,
+            },
+            Error::IrreversibleMigration { revision: 0 },
+            Error::MigrationLocked {
+                owner: String::new(),
+            },
+            Error::ArchiveError {
+                reason: String::new(),
+            },
+            Error::UnknownJob,
+            Error::JobClaimed {
+                owner: String::new(),
+            },
+        ]
+    }
+
+    /// Stops compiling when a variant is added, as a reminder to add it to
+    /// `every_error`.
+    fn is_listed(error: &Error) -> bool {
+        match error {
+            Error::LabelMe
+            | Error::AlreadyOnboarded
+            | Error::UsernameTaken
+            | Error::UnknownUser
+            | Error::AlreadyFriends
+            | Error::AlreadySentRequest
+            | Error::Blocked
+            | Error::BlockedByOther
+            | Error::NotFriends
+            | Error::UnknownChannel
+            | Error::UnknownAttachment
+            | Error::UnknownMessage
+            | Error::UnknownInvite
+            | Error::CannotEditMessage
+            | Error::CannotJoinCall
+            | Error::TooManyAttachments
+            | Error::TooManyReplies
+            | Error::EmptyMessage
+            | Error::CannotRemoveYourself
+            | Error::GroupTooLarge { .. }
+            | Error::AlreadyInGroup
+            | Error::NotInGroup
+            | Error::UnknownServer
+            | Error::UnknownMember
+            | Error::UnknownBan
+            | Error::InvalidRole
+            | Error::Banned
+            | Error::UnknownBot
+            | Error::ReachedMaximumBots
+            | Error::IsBot
+            | Error::BotIsPrivate
+            | Error::TooManyIds
+            | Error::FailedValidation { .. }
+            | Error::DatabaseError { .. }
+            | Error::InvalidDocument { .. }
+            | Error::InternalError
+            | Error::MissingPermission
+            | Error::InvalidOperation
+            | Error::InvalidCredentials
+            | Error::DuplicateNonce
+            | Error::VosoUnavailable
+            | Error::NoEffect
+            | Error::DatabaseTooNew { .. }
+            | Error::IrreversibleMigration { .. }
+            | Error::MigrationLocked { .. }
+            | Error::ArchiveError { .. }
+            | Error::UnknownJob
+            | Error::JobClaimed { .. } => true,
+        }
+    }
+
+    #[test]
+    fn catalogue_is_complete() {
+        let errors = every_error();
+        let kinds: HashSet<_> = errors.iter().map(|error| error.kind()).collect();
+        assert_eq!(kinds.len(), errors.len(), "every_error repeats a variant");
+
+        for error in &errors {
+            assert!(is_listed(error));
+
+            let info = error.info();
+            assert_eq!(json!(error)["type"], info.kind);
+            assert!(
+                ERROR_CATALOGUE
+                    .iter()
+                    .any(|entry| entry.kind == info.kind && entry.code == info.code),
+                "{} is missing from ERROR_CATALOGUE",
+                info.kind
+            );
+        }
+
+        assert_eq!(ERROR_CATALOGUE.len(), errors.len());
+    }
+
+    #[test]
+    fn catalogue_is_unique() {
+        let mut kinds = HashSet::new();
+        let mut codes = HashSet::new();
+        for info in ERROR_CATALOGUE {
+            assert!(kinds.insert(info.kind), "duplicate kind {}", info.kind);
+            assert!(codes.insert(info.code), "duplicate code {}", info.code);
+        }
+    }
+}